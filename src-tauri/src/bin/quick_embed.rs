@@ -15,7 +15,7 @@ async fn main() {
 
     println!("Starting quick embedding test for index: {} (max_files={:?}, batch_size={:?})", index_dir, max_files, batch_size);
 
-    match wayfinder_tauri::commands::generate_embeddings(index_dir, max_files, batch_size).await {
+    match wayfinder_tauri::commands::generate_embeddings(index_dir, max_files, batch_size, None).await {
         Ok(res) => println!("Embedding result: {}", serde_json::to_string_pretty(&res).unwrap_or_default()),
         Err(e) => eprintln!("Embedding failed: {}", e),
     }
@@ -1,22 +1,56 @@
 // Tauri command handlers - Pure Rust implementation
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
 use std::fs;
 use std::path::Path;
 use walkdir::WalkDir;
 use chrono::{DateTime, Local};
+use ordered_float::OrderedFloat;
 use rand::Rng;
 
 // Import git_assistant module from crate root
 use crate::git_assistant;
-
-// Azure OpenAI Configuration
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+use crate::embedding_provider;
+use crate::task_store;
+use crate::task_queue;
+use crate::index_store;
+use crate::error::{self, AppError};
+use crate::vector_index::{self, HnswIndex, HnswParams};
+use crate::embedding_template;
+use crate::storage_backend;
+use crate::observability;
+use crate::dump;
+
+// Embedding provider configuration. Field meaning shifts a bit by provider:
+// for "azure" all four fields apply; for "openai" only api_key and
+// deployment_name (the model name) are used; for "ollama" endpoint is the
+// server's base URL (defaults to http://localhost:11434) and
+// deployment_name is the model name, with api_key/api_version unused.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AzureConfig {
     pub endpoint: String,           // e.g., "https://your-resource.openai.azure.com"
     pub api_key: String,            // Your API key
     pub deployment_name: String,    // e.g., "text-embedding-ada-002"
     pub api_version: String,        // e.g., "2024-02-01"
+    #[serde(default = "default_embedding_provider")]
+    pub provider: String,           // "azure" | "openai" | "ollama" | "local"
+}
+
+impl Default for AzureConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            api_key: String::new(),
+            deployment_name: String::new(),
+            api_version: String::new(),
+            provider: default_embedding_provider(),
+        }
+    }
+}
+
+fn default_embedding_provider() -> String {
+    "azure".to_string()
 }
 
 // Embedding data stored per file
@@ -24,7 +58,10 @@ pub struct AzureConfig {
 pub struct FileEmbedding {
     pub path: String,
     pub embedding: Vec<f32>,        // 1536 dimensions for ada-002
-    pub content_hash: String,       // To detect if file changed
+    pub content_hash: String,       // To detect if this chunk's text changed
+    pub start_byte: usize,          // Offset of this chunk within the file
+    pub end_byte: usize,
+    pub chunk_index: usize,         // Position among the file's chunks, 0-based
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -32,6 +69,14 @@ pub struct EmbeddingsData {
     pub embeddings: Vec<FileEmbedding>,
     pub model: String,
     pub created_at: String,
+    // Defaulted so embeddings.json files from before multi-provider support
+    // still load; they're always Azure and will report a dimension
+    // mismatch (rather than a mysterious parse failure) if the provider
+    // change too.
+    #[serde(default = "default_embedding_provider")]
+    pub provider: String,
+    #[serde(default)]
+    pub dimensions: usize,
 }
 
 // Cluster data
@@ -63,6 +108,15 @@ pub struct FileEntry {
     pub size: u64,
     pub modified: String,
     pub extension: String,
+    // Set for one row of a CSV/TSV or one line of a JSONL/NDJSON file:
+    // `record_id` is that row/line's position within `parent_file`, which
+    // holds the real on-disk path (since `path` is a synthetic
+    // `{parent_file}#{record_id}` so each record still gets a unique,
+    // addressable entry in the index).
+    #[serde(default)]
+    pub record_id: Option<usize>,
+    #[serde(default)]
+    pub parent_file: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -72,12 +126,39 @@ pub struct IndexData {
     pub created_at: String,
 }
 
+/// One ranking rule's contribution to a `SearchResult`'s score, so the
+/// frontend can explain (or a debug/tuning mode can inspect) why a file
+/// ranked where it did instead of just seeing one opaque number.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum ScoreDetail {
+    KeywordName { weight: f32, matched: bool },
+    KeywordPath { weight: f32, matched: bool },
+    KeywordContent {
+        matched: bool,
+        match_count: usize,
+        /// Byte offsets of the match used for `preview`, so the frontend
+        /// can highlight it precisely instead of re-searching the preview text.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        match_start: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        match_end: Option<usize>,
+    },
+    Semantic { similarity: f32, rank: usize },
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SearchResult {
     pub path: String,
     pub name: String,
     pub score: f32,
     pub preview: Option<String>,
+    /// Which signal(s) contributed to `score`: `"keyword"`, `"semantic"`, or
+    /// both, so the frontend can badge a hit as a keyword match, a semantic
+    /// match, or a hybrid one.
+    pub matched_signals: Vec<String>,
+    /// Ordered breakdown of the ranking rules that fired for this hit.
+    pub score_details: Vec<ScoreDetail>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -188,6 +269,114 @@ fn log_error(index_dir: &Path, operation: &str, file_path: Option<&str>, error_m
 
 // Pure Rust command handlers - no Python dependency
 
+// File types ingested at record (row/line) granularity instead of as one
+// opaque blob, so a big CSV export or NDJSON dump is searchable/embeddable
+// per-row rather than per-file.
+const STRUCTURED_RECORD_EXTENSIONS: &[&str] = &["csv", "tsv", "jsonl", "ndjson"];
+
+/// Split a CSV/TSV or JSONL/NDJSON file into one `FileEntry` per data row.
+/// `path`/`name` get a `#{record_id}` suffix so each row is still a unique,
+/// addressable entry; `parent_file` keeps the real on-disk file so its
+/// content can be re-read later, and `record_id` is the row's position
+/// within it (the data row index for CSV/TSV, the line index for
+/// JSONL/NDJSON — both 0-based, counted after the header for CSV/TSV).
+fn parse_structured_records(path: &Path, content: &str, extension: &str, modified: &str) -> Vec<FileEntry> {
+    let raw_path = path.to_string_lossy().to_string();
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("unknown").to_string();
+
+    match extension {
+        "csv" | "tsv" => {
+            let delimiter = if extension == "tsv" { '\t' } else { ',' };
+            let mut lines = content.lines();
+            let header = match lines.next() {
+                Some(h) => h,
+                None => return Vec::new(),
+            };
+            let columns: Vec<String> = header.split(delimiter).map(|c| c.trim().to_string()).collect();
+
+            lines
+                .enumerate()
+                .filter(|(_, row)| !row.trim().is_empty())
+                .map(|(record_id, row)| {
+                    let values: Vec<&str> = row.split(delimiter).collect();
+                    let text = columns
+                        .iter()
+                        .zip(values.iter())
+                        .map(|(col, val)| format!("{}: {}", col, val.trim()))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+
+                    FileEntry {
+                        path: format!("{}#{}", raw_path, record_id),
+                        name: format!("{}#{}", file_name, record_id),
+                        size: text.len() as u64,
+                        modified: modified.to_string(),
+                        extension: extension.to_string(),
+                        record_id: Some(record_id),
+                        parent_file: Some(raw_path.clone()),
+                    }
+                })
+                .collect()
+        }
+        "jsonl" | "ndjson" => content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(record_id, line)| FileEntry {
+                path: format!("{}#{}", raw_path, record_id),
+                name: format!("{}#{}", file_name, record_id),
+                size: line.len() as u64,
+                modified: modified.to_string(),
+                extension: extension.to_string(),
+                record_id: Some(record_id),
+                parent_file: Some(raw_path.clone()),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Re-extract the text a single record's `FileEntry` represents by
+/// re-parsing `parent_file` the same way `parse_structured_records` did and
+/// picking out row/line `record_id`. Mirrors that function's indexing
+/// exactly so the two stay in sync.
+fn extract_record(content: &str, extension: &str, record_id: usize) -> Option<String> {
+    match extension {
+        "csv" | "tsv" => {
+            let delimiter = if extension == "tsv" { '\t' } else { ',' };
+            let mut lines = content.lines();
+            let header = lines.next()?;
+            let columns: Vec<&str> = header.split(delimiter).map(|c| c.trim()).collect();
+            let row = lines.nth(record_id)?;
+            let values: Vec<&str> = row.split(delimiter).collect();
+            Some(
+                columns
+                    .iter()
+                    .zip(values.iter())
+                    .map(|(col, val)| format!("{}: {}", col, val.trim()))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        }
+        "jsonl" | "ndjson" => content.lines().nth(record_id).map(|s| s.to_string()),
+        _ => None,
+    }
+}
+
+/// Read the text a `FileEntry` represents: the whole file for a normal
+/// entry, or just its one row/line for a CSV/JSONL record (re-read from
+/// `parent_file`, since `path` is a synthetic identifier, not a real file).
+fn read_entry_content(file: &FileEntry) -> Result<String, String> {
+    match (&file.parent_file, file.record_id) {
+        (Some(parent), Some(record_id)) => {
+            let raw = fs::read_to_string(parent).map_err(|e| e.to_string())?;
+            extract_record(&raw, &file.extension, record_id)
+                .ok_or_else(|| format!("Record {} not found in {}", record_id, parent))
+        }
+        _ => fs::read_to_string(&file.path).map_err(|e| e.to_string()),
+    }
+}
+
 /// Scan a directory and create an index of text files
 #[tauri::command(rename_all = "camelCase")]
 pub async fn scan_directory(path: String, index_dir: String) -> Result<serde_json::Value, String> {
@@ -236,13 +425,29 @@ pub async fn scan_directory(path: String, index_dir: String) -> Result<serde_jso
                 .and_then(|e| e.to_str())
                 .unwrap_or("")
                 .to_lowercase();
-            
+
+            if STRUCTURED_RECORD_EXTENSIONS.contains(&ext.as_str()) {
+                if let Ok(metadata) = fs::metadata(file_path) {
+                    let modified = metadata.modified()
+                        .ok()
+                        .and_then(|t| DateTime::<Local>::from(t).format("%Y-%m-%d %H:%M:%S").to_string().into())
+                        .unwrap_or_else(|| "Unknown".to_string());
+
+                    if let Ok(content) = fs::read_to_string(file_path) {
+                        let records = parse_structured_records(file_path, &content, &ext, &modified);
+                        total_size += records.iter().map(|r| r.size).sum::<u64>();
+                        files.extend(records);
+                    }
+                }
+                continue;
+            }
+
             // Only index text files
             if text_extensions.contains(&ext.as_str()) {
                 if let Ok(metadata) = fs::metadata(file_path) {
                     let size = metadata.len();
                     total_size += size;
-                    
+
                     let modified = metadata.modified()
                         .ok()
                         .and_then(|t| DateTime::<Local>::from(t).format("%Y-%m-%d %H:%M:%S").to_string().into())
@@ -257,6 +462,8 @@ pub async fn scan_directory(path: String, index_dir: String) -> Result<serde_jso
                         size,
                         modified,
                         extension: ext,
+                        record_id: None,
+                        parent_file: None,
                     });
                 }
             }
@@ -273,22 +480,28 @@ pub async fn scan_directory(path: String, index_dir: String) -> Result<serde_jso
     fs::create_dir_all(&index_path)
         .map_err(|e| format!("Failed to create index directory: {}", e))?;
 
-    // Save index data
-    let index_data = IndexData {
-        files: files.clone(),
-        scan_path: path.clone(),
-        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-    };
-
-    let index_file = index_path.join("index.json");
-    let json = serde_json::to_string_pretty(&index_data)
-        .map_err(|e| format!("Failed to serialize index: {}", e))?;
-    
-    fs::write(&index_file, json)
-        .map_err(|e| format!("Failed to write index file: {}", e))?;
+    // Save index data through whichever backend is configured for this
+    // index (`"json"` by default), so a fresh scan goes through the same
+    // `IndexStore::upsert_file` path `generate_embeddings` reads back from.
+    let index_dir_str = index_path.to_string_lossy().to_string();
+    let backend = index_store::configured_backend(&index_dir_str);
+    let store = index_store::open_index_store_for(&index_dir_str)?;
+    // A fresh scan replaces the file list wholesale (so files deleted since
+    // the last scan actually drop out of the index), then repopulates it
+    // through the same per-file upsert path a resumed scan would use.
+    index_store::reset_files(&index_dir_str, &backend)?;
+    for file in &files {
+        store.upsert_file(file)?;
+    }
+    index_store::set_scan_metadata(
+        &index_dir_str,
+        &backend,
+        &path,
+        &Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    )?;
 
     println!("[RUST] Scan complete - {} files found, {} bytes total", files.len(), total_size);
-    println!("[RUST] Index written to: {}", index_file.display());
+    println!("[RUST] Index written to: {}", index_path.display());
     
     Ok(serde_json::json!({
         "files_scanned": files.len(),
@@ -297,106 +510,203 @@ pub async fn scan_directory(path: String, index_dir: String) -> Result<serde_jso
     }))
 }
 
-/// Generate embeddings using Azure OpenAI with auto-batching and progress saving
+// Target size for a single embedding chunk, and how much of the previous
+// chunk's tail carries forward so a split doesn't orphan context.
+const CHUNK_TARGET_CHARS: usize = 6000;
+const CHUNK_OVERLAP_CHARS: usize = 400;
+
+// Extensions whose "natural" boundary is a code construct rather than a
+// blank line between paragraphs.
+const CODE_EXTENSIONS: &[&str] = &["rs", "py", "js", "ts", "jsx", "tsx", "java", "go", "c", "cpp", "h", "hpp", "cs"];
+
+struct TextChunk {
+    text: String,
+    start_byte: usize,
+    end_byte: usize,
+}
+
+/// Split file content into overlapping chunks instead of truncating it, so
+/// long files stay fully searchable/embeddable. Prefers breaking on a blank
+/// line (prose) or a code boundary like `fn `/`class `/`}` (code) over
+/// cutting mid-thought at the target length.
+fn chunk_file_content(content: &str, extension: &str) -> Vec<TextChunk> {
+    if content.len() <= CHUNK_TARGET_CHARS {
+        return vec![TextChunk {
+            text: content.to_string(),
+            start_byte: 0,
+            end_byte: content.len(),
+        }];
+    }
+
+    let is_code = CODE_EXTENSIONS.contains(&extension.to_lowercase().as_str());
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < content.len() {
+        let target_end = (start + CHUNK_TARGET_CHARS).min(content.len());
+        let end = if target_end >= content.len() {
+            content.len()
+        } else {
+            find_chunk_boundary(content, start, target_end, is_code)
+        };
+
+        chunks.push(TextChunk {
+            text: content[start..end].to_string(),
+            start_byte: start,
+            end_byte: end,
+        });
+
+        if end >= content.len() {
+            break;
+        }
+
+        // Carry a bit of the previous chunk forward so a concept split
+        // across the boundary still appears whole in at least one chunk.
+        start = end.saturating_sub(CHUNK_OVERLAP_CHARS);
+        // Always make forward progress even if overlap would stall us.
+        if start <= chunks.last().map(|c| c.start_byte).unwrap_or(0) {
+            start = end;
+        }
+    }
+
+    chunks
+}
+
+/// Search backwards from `target_end` (within `[search_start, target_end]`)
+/// for the best place to end a chunk, preferring a blank line for prose or
+/// a code-construct boundary for source files. Falls back to `target_end`
+/// (an arbitrary char boundary) if nothing better is found nearby.
+fn find_chunk_boundary(content: &str, search_start: usize, target_end: usize, is_code: bool) -> usize {
+    let window_start = search_start.max(target_end.saturating_sub(CHUNK_TARGET_CHARS / 4));
+    let window = &content[window_start..target_end];
+
+    if is_code {
+        for marker in ["\nfn ", "\npub fn ", "\nclass ", "\ndef ", "\n}\n"] {
+            if let Some(pos) = window.rfind(marker) {
+                return window_start + pos + 1;
+            }
+        }
+    }
+
+    if let Some(pos) = window.rfind("\n\n") {
+        return window_start + pos + 2;
+    }
+
+    // Last resort: land on the nearest char boundary at or before target_end.
+    let mut end = target_end;
+    while end > search_start && !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    end
+}
+
+/// Generate embeddings through the configured provider, with auto-batching and progress saving.
+/// When `task_id` names a task previously created with `enqueue_task`, this marks it
+/// `Processing`, mirrors `BatchProgress` into its `details` as the run proceeds, checks for
+/// cancellation once per file, and marks it finished when the run ends.
 #[tauri::command(rename_all = "camelCase")]
-pub async fn generate_embeddings(index_dir: String, max_files: Option<usize>, batch_size: Option<usize>) -> Result<serde_json::Value, String> {
+pub async fn generate_embeddings(index_dir: String, max_files: Option<usize>, batch_size: Option<usize>, task_id: Option<String>) -> Result<serde_json::Value, String> {
     println!("[RUST] generate_embeddings called for: {}", index_dir);
+
+    if let Some(ref id) = task_id {
+        task_store::start_task(&index_dir, id)?;
+    }
     
     let index_path = Path::new(&index_dir);
-    let index_file = index_path.join("index.json");
     let config_file = index_path.join("azure_config.json");
-    let embeddings_file = index_path.join("embeddings.json");
     let progress_file = index_path.join("embedding_progress.json");
-    
+
     // Configuration
     let config_batch_size = batch_size.unwrap_or(100);
     let save_interval = 50; // Save every 50 files
     let delay_ms = 50; // 50ms delay between requests
-    
+
+    let backend = index_store::configured_backend(&index_dir);
+
     // Check if index exists
-    if !index_file.exists() {
-        return Err("Index not found. Please scan a directory first.".to_string());
+    if !index_store::index_exists(&index_dir, &backend) {
+        return Err(AppError::IndexNotFound { index_dir: index_dir.clone() }.into());
     }
-    
+
     // Load Azure config
     if !config_file.exists() {
-        return Err("Azure config not found. Please configure Azure OpenAI settings first.".to_string());
+        return Err(AppError::ConfigMissing { what: "Azure config".to_string() }.into());
     }
-    
+
     let config_content = fs::read_to_string(&config_file)
         .map_err(|e| format!("Failed to read Azure config: {}", e))?;
     let config: AzureConfig = serde_json::from_str(&config_content)
         .map_err(|e| format!("Failed to parse Azure config: {}", e))?;
-    
-    if config.endpoint.is_empty() || config.api_key.is_empty() || config.deployment_name.is_empty() {
-        return Err("Azure config is incomplete. Please set endpoint, API key, and deployment name.".to_string());
-    }
-    
+
+    let provider = embedding_provider::build_provider(&config)?;
+    let provider_name = provider.name();
+    let provider_dimensions = provider.dimensions();
+
+    let store = index_store::open_index_store_for(&index_dir)?;
+
     // Load index
-    let index_content = fs::read_to_string(&index_file)
-        .map_err(|e| format!("Failed to read index: {}", e))?;
-    let index_data: IndexData = serde_json::from_str(&index_content)
-        .map_err(|e| format!("Failed to parse index: {}", e))?;
-    
+    let index_files = store.get_files()?;
+
     // Apply max_files limit if specified
     let files_to_process: Vec<FileEntry> = if let Some(max) = max_files {
-        index_data.files.into_iter().take(max).collect()
+        index_files.into_iter().take(max).collect()
     } else {
-        index_data.files
+        index_files
     };
-    
+
     let total_files = files_to_process.len();
     let total_batches = (total_files + config_batch_size - 1) / config_batch_size;
-    
+
     println!("[RUST] Processing {} files in {} batches of {}", total_files, total_batches, config_batch_size);
-    
-    // Load existing embeddings (for caching and resuming)
-    let mut existing_embeddings: HashMap<String, FileEmbedding> = HashMap::new();
-    if embeddings_file.exists() {
-        if let Ok(content) = fs::read_to_string(&embeddings_file) {
-            if let Ok(data) = serde_json::from_str::<EmbeddingsData>(&content) {
-                println!("[RUST] Loaded {} existing embeddings from cache", data.embeddings.len());
-                for emb in data.embeddings {
-                    existing_embeddings.insert(emb.path.clone(), emb);
-                }
-            }
+
+    // Load existing embedding chunks (for caching and resuming), keyed by
+    // (path, chunk_index) now that a file can produce more than one. A
+    // cache built with a different provider/model is dropped rather than
+    // reused, since its vectors live in a different embedding space.
+    let existing_meta = index_store::get_embeddings_meta(&index_dir, &backend);
+    let mut existing_chunks: HashMap<(String, usize), FileEmbedding> = HashMap::new();
+    if existing_meta.provider == provider_name && existing_meta.dimensions == provider_dimensions {
+        let cached = store.get_embeddings()?;
+        println!("[RUST] Loaded {} existing embedding chunks from cache", cached.len());
+        for emb in cached {
+            existing_chunks.insert((emb.path.clone(), emb.chunk_index), emb);
         }
+    } else if !existing_meta.provider.is_empty() {
+        println!(
+            "[RUST] Ignoring cached embeddings from '{}' ({} dims); current provider is '{}' ({} dims)",
+            existing_meta.provider, existing_meta.dimensions, provider_name, provider_dimensions
+        );
     }
-    
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(60))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-    
-    let mut new_embeddings: Vec<FileEmbedding> = existing_embeddings.values().cloned().collect();
-    let processed_paths: std::collections::HashSet<String> = existing_embeddings.keys().cloned().collect();
-    
+
+    // What text actually gets embedded for each file, e.g. a title/path
+    // header plus a truncated excerpt instead of the whole raw file.
+    // Rendered before chunking, so changing the template changes the
+    // chunk text (and therefore its content hash), which is what makes the
+    // cache-skip logic below correctly invalidate on a template change.
+    let template = embedding_template::get_template(&index_dir);
+
+    // Mirror each generated vector into the shared Postgres/pgvector store
+    // alongside the per-index `embeddings.json`, when the `pgvector`
+    // feature is built in and `DATABASE_URL` is configured. Rows are
+    // buffered and flushed in batches (see the `save_interval` checkpoint
+    // below) rather than one round-trip per chunk.
+    #[cfg(feature = "pgvector")]
+    let pgvector_store = match std::env::var("DATABASE_URL") {
+        Ok(url) => Some(crate::vector_store::PgVectorStore::connect(&url, provider_dimensions).await?),
+        Err(_) => None,
+    };
+    #[cfg(feature = "pgvector")]
+    let mut pgvector_pending: Vec<(String, i32, Vec<f32>, serde_json::Value)> = Vec::new();
+
+    let mut total_embeddings_count = 0;
+
     let mut cached_count = 0;
     let mut generated_count = 0;
     let mut error_count = 0;
     let mut skipped_count = 0;
-    
-    let mut api_version = if config.api_version.is_empty() { 
-        "2024-02-01".to_string() 
-    } else { 
-        config.api_version.clone() 
-    };
-    
-    // Normalize endpoint to avoid duplicate /openai segments
-    let mut base = config.endpoint.trim_end_matches('/').to_string();
-    if !base.ends_with("/openai") && !base.ends_with("/openai/") {
-        base = format!("{}/openai", base);
-    }
 
-    let url = format!(
-        "{}/deployments/{}/embeddings?api-version={}",
-        base,
-        config.deployment_name,
-        api_version
-    );
+    println!("[RUST] Embedding provider: {}", provider_name);
 
-    println!("[RUST] Embedding API URL: {}", url);
-    
     // Initialize progress
     let mut progress = BatchProgress {
         batch_id: format!("{}", Local::now().timestamp()),
@@ -415,144 +725,100 @@ pub async fn generate_embeddings(index_dir: String, max_files: Option<usize>, ba
     let _ = fs::write(&progress_file, serde_json::to_string_pretty(&progress).unwrap_or_default());
     
     for (i, file) in files_to_process.iter().enumerate() {
-        // Skip if already processed
-        if processed_paths.contains(&file.path) {
-            cached_count += 1;
-            continue;
+        // Check for cancellation once per file rather than mid-chunk, so a
+        // canceled run still stops with consistent per-file progress.
+        if let Some(ref id) = task_id {
+            if task_store::is_canceled(id) {
+                println!("[RUST] generate_embeddings canceled at file {}/{}", i, total_files);
+                progress.status = "canceled".to_string();
+                break;
+            }
         }
-        
-        // Read file content
-        let content = match fs::read_to_string(&file.path) {
+
+        // Read file content (or, for a CSV/JSONL record, just its one row)
+        let content = match read_entry_content(file) {
             Ok(c) => c,
             Err(e) => {
                 skipped_count += 1;
-                log_error(&index_path, "read_file", Some(&file.path), &e.to_string(), None);
+                let err = AppError::FileRead { path: file.path.clone(), reason: e };
+                log_error(&index_path, "read_file", Some(&file.path), &err.to_string(), Some(err.code()));
                 continue;
             }
         };
-        
+
         // Skip empty files
         if content.trim().is_empty() {
             skipped_count += 1;
             continue;
         }
-        
-        // Simple hash of content for caching
-        let content_hash = format!("{:x}", md5_hash(&content));
-        
-        // Truncate content to ~8000 tokens (roughly 32000 chars)
-        let truncated_content = if content.len() > 32000 {
-            content[..32000].to_string()
-        } else {
-            content.clone()
-        };
-        
-        // Call Azure OpenAI with retry logic
-        let request_body = serde_json::json!({
-            "input": truncated_content
-        });
-        
-        let mut retries = 0;
-        let max_retries = 3;
-        let mut success = false;
-        
-        while retries < max_retries && !success {
-            let url_current = format!("{}/deployments/{}/embeddings?api-version={}", base, config.deployment_name, api_version);
-            match client
-                .post(&url_current)
-                .header("api-key", &config.api_key)
-                .header("Content-Type", "application/json")
-                .json(&request_body)
-                .send()
-                .await
-            {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        match response.json::<serde_json::Value>().await {
-                            Ok(json) => {
-                                // Check for explicit error field
-                                if json.get("error").is_some() {
-                                    let err_text = json["error"].to_string();
-                                    log_error(&index_path, "api_error", Some(&file.path), &err_text, None);
-                                    progress.errors.push(format!("{}: API error - {}", file.name, err_text));
-                                    error_count += 1;
-                                } else if let Some(embedding) = json["data"][0]["embedding"].as_array() {
-                                    let emb_vec: Vec<f32> = embedding
-                                        .iter()
-                                        .filter_map(|v| v.as_f64().map(|f| f as f32))
-                                        .collect();
-
-                                    new_embeddings.push(FileEmbedding {
-                                        path: file.path.clone(),
-                                        embedding: emb_vec,
-                                        content_hash: content_hash.clone(),
-                                    });
-                                    generated_count += 1;
-                                    success = true;
-                                } else {
-                                    // Unexpected response shape
-                                    let err_text = json.to_string();
-                                    log_error(&index_path, "api_error", Some(&file.path), &format!("Unexpected response: {}", err_text), None);
-                                    progress.errors.push(format!("{}: Unexpected response shape", file.name));
-                                    error_count += 1;
-                                }
-                            }
-                            Err(e) => {
-                                log_error(&index_path, "parse_error", Some(&file.path), &format!("Failed to parse JSON: {}", e), None);
-                                progress.errors.push(format!("{}: Failed to parse JSON", file.name));
-                                error_count += 1;
-                            }
-                        }
-                    } else if response.status().as_u16() == 429 {
-                        // Rate limited - wait and retry
-                        let wait_time = 2u64.pow(retries as u32) * 1000;
-                        println!("[RUST] Rate limited, waiting {}ms...", wait_time);
-                        log_error(&index_path, "rate_limit", Some(&file.path), "Rate limited by Azure", Some("429"));
-                        tokio::time::sleep(tokio::time::Duration::from_millis(wait_time)).await;
-                        retries += 1;
-                    } else {
-                        let status = response.status();
-                        let error_text = response.text().await.unwrap_or_default();
-
-                        // Detect unsupported API version and attempt a fallback once
-                        if error_text.contains("API version not supported") {
-                            if api_version != "2023-10-01" {
-                                println!("[RUST] API version not supported, attempting fallback to 2023-10-01");
-                                api_version = "2023-10-01".to_string();
-                                // Rebuild URL with fallback API version
-                                base = config.endpoint.trim_end_matches('/').to_string();
-                                if !base.ends_with("/openai") && !base.ends_with("/openai/") {
-                                    base = format!("{}/openai", base);
-                                }
-                                // Update URL for subsequent requests
-                                // Note: the env URL variable will be overwritten in the outer scope for subsequent calls
-                                // Reset retries for this file so we try again with the new version
-                                retries = 0;
-                                continue; // retry this request with new api_version
-                            }
-                        }
-
-                        log_error(&index_path, "api_error", Some(&file.path), &error_text, Some(&status.to_string()));
-                        error_count += 1;
-                        progress.errors.push(format!("{}: {} - {}", file.name, status, error_text));
-                        break;
+
+        // Run the file through the configured embedding template before
+        // chunking, so e.g. a title/path header can be embedded alongside
+        // (or instead of) the raw content. `render` never fails; an
+        // unrecognized `{{ field }}` is just dropped from the output.
+        let rendered_content = embedding_template::render(&template.template, file, &content).rendered;
+
+        // Split into overlapping, semantically-aligned chunks so a long
+        // file is embedded as several focused vectors instead of one
+        // muddy one truncated at the first ~8k tokens.
+        let chunks = chunk_file_content(&rendered_content, &file.extension);
+
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            // Hash the chunk text itself, so only the chunks that actually
+            // changed are re-embedded on a re-run.
+            let content_hash = format!("{:x}", md5_hash(&chunk.text));
+
+            if let Some(cached) = existing_chunks.get(&(file.path.clone(), chunk_index)) {
+                if cached.content_hash == content_hash {
+                    store.upsert_embedding(cached)?;
+                    total_embeddings_count += 1;
+                    cached_count += 1;
+                    continue;
+                }
+            }
+
+            // Provider-specific retry/rate-limit/fallback handling lives
+            // behind the trait; this loop just asks for a vector back.
+            match provider.embed(std::slice::from_ref(&chunk.text)).await {
+                Ok(mut vectors) => {
+                    let embedding = FileEmbedding {
+                        path: file.path.clone(),
+                        embedding: vectors.remove(0),
+                        content_hash: content_hash.clone(),
+                        start_byte: chunk.start_byte,
+                        end_byte: chunk.end_byte,
+                        chunk_index,
+                    };
+                    // Upsert this one row immediately instead of
+                    // accumulating every vector in memory and rewriting
+                    // the whole embeddings file on each checkpoint.
+                    store.upsert_embedding(&embedding)?;
+
+                    #[cfg(feature = "pgvector")]
+                    if pgvector_store.is_some() {
+                        pgvector_pending.push((
+                            embedding.path.clone(),
+                            embedding.chunk_index as i32,
+                            embedding.embedding.clone(),
+                            serde_json::json!({ "content_hash": embedding.content_hash }),
+                        ));
                     }
+
+                    total_embeddings_count += 1;
+                    generated_count += 1;
                 }
                 Err(e) => {
-                    if retries < max_retries - 1 {
-                        let wait_time = 2u64.pow(retries as u32) * 500;
-                        tokio::time::sleep(tokio::time::Duration::from_millis(wait_time)).await;
-                        retries += 1;
-                    } else {
-                        log_error(&index_path, "request_error", Some(&file.path), &e.to_string(), None);
-                        error_count += 1;
-                        progress.errors.push(format!("{}: {}", file.name, e));
-                        break;
-                    }
+                    let code = error::classify_provider_error(&provider_name, &e).map(|err| err.code());
+                    log_error(&index_path, "api_error", Some(&file.path), &e, code);
+                    progress.errors.push(format!("{}: {}", file.name, e));
+                    error_count += 1;
                 }
             }
+
+            // Delay between requests
+            tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
         }
-        
+
         // Update progress
         progress.processed_files = i + 1;
         progress.current_batch = (i / config_batch_size) + 1;
@@ -565,48 +831,55 @@ pub async fn generate_embeddings(index_dir: String, max_files: Option<usize>, ba
             
             // Save progress file
             let _ = fs::write(&progress_file, serde_json::to_string_pretty(&progress).unwrap_or_default());
-            
-            // Save embeddings periodically
-            let embeddings_data = EmbeddingsData {
-                embeddings: new_embeddings.clone(),
-                model: config.deployment_name.clone(),
-                created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-            };
-            
-            if let Ok(json) = serde_json::to_string_pretty(&embeddings_data) {
-                let _ = fs::write(&embeddings_file, json);
+
+            if let Some(ref id) = task_id {
+                let _ = task_store::update_task_details(&index_dir, id, serde_json::to_value(&progress).unwrap_or_default());
+            }
+
+            #[cfg(feature = "pgvector")]
+            if let Some(ref pg) = pgvector_store {
+                if !pgvector_pending.is_empty() {
+                    pg.upsert_batch(&std::mem::take(&mut pgvector_pending)).await?;
+                }
             }
         }
-        
-        // Delay between requests
-        tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
     }
-    
+
+    // Flush whatever the last checkpoint didn't (a canceled run, or a
+    // skipped final file that never reached the checkpoint above).
+    #[cfg(feature = "pgvector")]
+    if let Some(ref pg) = pgvector_store {
+        if !pgvector_pending.is_empty() {
+            pg.upsert_batch(&std::mem::take(&mut pgvector_pending)).await?;
+        }
+    }
+
     // Final save
-    progress.status = "complete".to_string();
+    if progress.status != "canceled" {
+        progress.status = "complete".to_string();
+    }
     progress.last_updated = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
     let _ = fs::write(&progress_file, serde_json::to_string_pretty(&progress).unwrap_or_default());
-    
-    let embeddings_data = EmbeddingsData {
-        embeddings: new_embeddings.clone(),
-        model: config.deployment_name.clone(),
-        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-    };
-    
-    let json = serde_json::to_string_pretty(&embeddings_data)
-        .map_err(|e| format!("Failed to serialize embeddings: {}", e))?;
-    
-    fs::write(&embeddings_file, json)
-        .map_err(|e| format!("Failed to write embeddings file: {}", e))?;
-    
-    println!("[RUST] Embeddings complete: {} generated, {} cached, {} skipped, {} errors", 
+
+    index_store::set_embeddings_meta(
+        &index_dir,
+        &backend,
+        &index_store::EmbeddingsMeta {
+            provider: provider_name.clone(),
+            model: config.deployment_name.clone(),
+            dimensions: provider_dimensions,
+            created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        },
+    )?;
+
+    println!("[RUST] Embeddings complete: {} generated, {} cached, {} skipped, {} errors",
         generated_count, cached_count, skipped_count, error_count);
 
     // If there were many errors or nothing was generated, write a diagnostic file to help debugging
     if error_count > 0 && generated_count == 0 {
         let diag_file = index_path.join("embedding_diagnostic.json");
         let diag = serde_json::json!({
-            "url_attempted": format!("{}/deployments/{}/embeddings?api-version={}", base, config.deployment_name, api_version),
+            "provider": provider_name,
             "generated": generated_count,
             "cached": cached_count,
             "skipped": skipped_count,
@@ -617,14 +890,20 @@ pub async fn generate_embeddings(index_dir: String, max_files: Option<usize>, ba
             let _ = fs::write(&diag_file, djson);
         }
     }
-    
+
+    if let Some(ref id) = task_id {
+        // `finish_task` keeps a `Canceled` status even if `success` is true
+        // here, so a midway cancellation isn't overwritten as `Succeeded`.
+        task_store::finish_task(&index_dir, id, true, serde_json::to_value(&progress).unwrap_or_default())?;
+    }
+
     Ok(serde_json::json!({
         "embeddings_generated": generated_count,
         "cached_count": cached_count,
         "skipped_count": skipped_count,
         "error_count": error_count,
-        "total_files": new_embeddings.len(),
-        "message": format!("Generated {} new embeddings, {} from cache, {} skipped, {} errors", 
+        "total_files": total_embeddings_count,
+        "message": format!("Generated {} new embeddings, {} from cache, {} skipped, {} errors",
             generated_count, cached_count, skipped_count, error_count)
     }))
 }
@@ -757,6 +1036,70 @@ pub async fn create_clusters(index_dir: String, num_clusters: Option<usize>) ->
     }))
 }
 
+/// Cluster the stored embeddings with k-means++ initialization instead of
+/// `create_clusters`'s uniformly-random one, which spreads the starting
+/// centroids out and tends to converge in fewer iterations. Runs
+/// `KMEANS_RESTARTS` independent restarts and keeps the lowest-distortion
+/// one; when `k` is omitted, sweeps a small range of candidate cluster
+/// counts and picks the one with the best mean silhouette score instead of
+/// a fixed sqrt(N) guess.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn cluster_embeddings(index_dir: String, k: Option<usize>) -> Result<serde_json::Value, String> {
+    println!("[RUST] cluster_embeddings called for: {}", index_dir);
+
+    let index_path = Path::new(&index_dir);
+    let embeddings_file = index_path.join("embeddings.json");
+    let clusters_file = index_path.join("clusters.json");
+
+    if !embeddings_file.exists() {
+        return Err("Embeddings not found. Please generate embeddings first.".to_string());
+    }
+
+    let content = fs::read_to_string(&embeddings_file)
+        .map_err(|e| format!("Failed to read embeddings: {}", e))?;
+    let embeddings_data: EmbeddingsData = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse embeddings: {}", e))?;
+
+    if embeddings_data.embeddings.is_empty() {
+        return Err("No embeddings found. Please generate embeddings first.".to_string());
+    }
+
+    let normalized: Vec<Vec<f32>> = embeddings_data.embeddings.iter().map(|e| normalize_vector(&e.embedding)).collect();
+
+    let k = match k {
+        Some(k) => k,
+        None => auto_select_k(&embeddings_data.embeddings, &normalized),
+    };
+
+    println!(
+        "[RUST] Clustering {} files into {} clusters (k-means++, {} restarts)",
+        embeddings_data.embeddings.len(),
+        k,
+        KMEANS_RESTARTS
+    );
+
+    let clusters = kmeans_plusplus_cluster(&embeddings_data.embeddings, k, KMEANS_RESTARTS);
+
+    let clusters_data = ClustersData {
+        clusters: clusters.clone(),
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+
+    let json = serde_json::to_string_pretty(&clusters_data)
+        .map_err(|e| format!("Failed to serialize clusters: {}", e))?;
+
+    fs::write(&clusters_file, json)
+        .map_err(|e| format!("Failed to write clusters file: {}", e))?;
+
+    println!("[RUST] Clustering complete: {} clusters created", clusters.len());
+
+    Ok(serde_json::json!({
+        "clusters_created": clusters.len(),
+        "total_files": embeddings_data.embeddings.len(),
+        "message": format!("Created {} clusters from {} files", clusters.len(), embeddings_data.embeddings.len())
+    }))
+}
+
 /// K-means clustering implementation
 fn kmeans_cluster(embeddings: &[FileEmbedding], k: usize) -> Vec<Cluster> {
     if embeddings.is_empty() || k == 0 {
@@ -856,38 +1199,305 @@ fn kmeans_cluster(embeddings: &[FileEmbedding], k: usize) -> Vec<Cluster> {
     clusters
 }
 
-/// Generate a descriptive label for a cluster based on its files
-fn generate_cluster_label(file_paths: &[String]) -> String {
-    use std::collections::HashMap;
-    
-    let mut dir_counts: HashMap<String, usize> = HashMap::new();
-    let mut ext_counts: HashMap<String, usize> = HashMap::new();
-    let mut word_counts: HashMap<String, usize> = HashMap::new();
-    
-    // Common words to ignore
-    let stopwords: std::collections::HashSet<&str> = [
-        "the", "a", "an", "and", "or", "but", "in", "on", "at", "to", "for",
-        "of", "with", "by", "from", "as", "is", "was", "are", "were", "been",
-        "be", "have", "has", "had", "do", "does", "did", "will", "would", "could",
-        "should", "may", "might", "must", "shall", "can", "need", "dare", "ought",
-        "used", "index", "main", "test", "spec", "temp", "tmp", "copy", "new", "old"
-    ].iter().cloned().collect();
-    
-    for path in file_paths {
-        let path_obj = Path::new(path);
-        
-        // Count parent directories
-        if let Some(parent) = path_obj.parent() {
-            if let Some(dir_name) = parent.file_name() {
-                let dir = dir_name.to_string_lossy().to_lowercase();
-                if !dir.is_empty() && dir.len() > 1 {
-                    *dir_counts.entry(dir).or_insert(0) += 1;
-                }
-            }
-        }
-        
-        // Count extensions
-        if let Some(ext) = path_obj.extension() {
+/// Number of independent k-means++ restarts `kmeans_plusplus_cluster` runs
+/// before keeping the lowest-distortion one; a single run can land in a
+/// bad local optimum depending on its initial D² draw.
+const KMEANS_RESTARTS: usize = 5;
+
+fn dot_product(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+/// One run of k-means++ over already unit-normalized vectors: the first
+/// centroid is picked uniformly at random, then each subsequent one with
+/// probability proportional to its squared distance from the nearest
+/// centroid chosen so far (D² sampling), which spreads the initial
+/// centroids out instead of risking two landing close together. Runs
+/// Lloyd's algorithm to convergence (or `max_iterations`), re-seeding any
+/// centroid that ends up with no members to the point farthest from its
+/// own cluster's centroid rather than letting it sit empty. Returns the
+/// final centroids, each point's cluster assignment, and the total
+/// distortion (sum of cosine distances from each point to its centroid).
+fn kmeans_plusplus_once(normalized: &[Vec<f32>], k: usize, rng: &mut impl Rng) -> (Vec<Vec<f32>>, Vec<usize>, f32) {
+    let dim = normalized[0].len();
+    let mut centroids: Vec<Vec<f32>> = vec![normalized[rng.gen_range(0..normalized.len())].clone()];
+
+    while centroids.len() < k {
+        let weights: Vec<f32> = normalized
+            .iter()
+            .map(|v| {
+                centroids
+                    .iter()
+                    .map(|c| {
+                        let dist = 1.0 - dot_product(v, c);
+                        dist * dist
+                    })
+                    .fold(f32::MAX, f32::min)
+            })
+            .collect();
+
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 {
+            // Every remaining point already coincides with a chosen
+            // centroid; fall back to a uniform pick so we still reach k.
+            centroids.push(normalized[rng.gen_range(0..normalized.len())].clone());
+            continue;
+        }
+
+        let mut target = rng.gen_range(0.0..total);
+        let mut chosen = normalized.len() - 1;
+        for (i, w) in weights.iter().enumerate() {
+            if target < *w {
+                chosen = i;
+                break;
+            }
+            target -= w;
+        }
+        centroids.push(normalized[chosen].clone());
+    }
+
+    let mut assignments: Vec<usize> = vec![0; normalized.len()];
+    let max_iterations = 50;
+
+    for iteration in 0..max_iterations {
+        let mut changed = false;
+
+        for (i, v) in normalized.iter().enumerate() {
+            let mut best_idx = 0;
+            let mut best_sim = f32::MIN;
+            for (j, centroid) in centroids.iter().enumerate() {
+                let sim = dot_product(v, centroid);
+                if sim > best_sim {
+                    best_sim = sim;
+                    best_idx = j;
+                }
+            }
+            if assignments[i] != best_idx {
+                assignments[i] = best_idx;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            println!("[RUST] K-means++ converged at iteration {}", iteration);
+            break;
+        }
+
+        let mut sums = vec![vec![0.0f32; dim]; centroids.len()];
+        let mut counts = vec![0usize; centroids.len()];
+        for (i, v) in normalized.iter().enumerate() {
+            let j = assignments[i];
+            counts[j] += 1;
+            for (d, val) in v.iter().enumerate() {
+                sums[j][d] += val;
+            }
+        }
+        for j in 0..centroids.len() {
+            if counts[j] > 0 {
+                for val in sums[j].iter_mut() {
+                    *val /= counts[j] as f32;
+                }
+                centroids[j] = normalize_vector(&sums[j]);
+            }
+        }
+
+        // Empty clusters don't get a mean to recenter on; re-seed them to
+        // the point currently farthest from its own cluster's centroid so
+        // the restart doesn't quietly converge on fewer than k clusters.
+        for j in 0..centroids.len() {
+            if counts[j] > 0 {
+                continue;
+            }
+            if let Some((farthest, _)) = normalized
+                .iter()
+                .enumerate()
+                .map(|(i, v)| (i, 1.0 - dot_product(v, &centroids[assignments[i]])))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            {
+                centroids[j] = normalized[farthest].clone();
+                assignments[farthest] = j;
+            }
+        }
+    }
+
+    let distortion: f32 = normalized
+        .iter()
+        .zip(assignments.iter())
+        .map(|(v, &j)| 1.0 - dot_product(v, &centroids[j]))
+        .sum();
+
+    (centroids, assignments, distortion)
+}
+
+/// Mean silhouette coefficient of an assignment: for each point, `a` is its
+/// mean cosine distance to the rest of its own cluster and `b` is the
+/// lowest mean distance to any other cluster; silhouette =
+/// `(b - a) / max(a, b)`, averaged over all points. `auto_select_k` sweeps
+/// candidate `k` values and picks the one that maximizes this, since it
+/// rewards clusters that are both tight and well-separated.
+fn silhouette_score(normalized: &[Vec<f32>], assignments: &[usize], k: usize) -> f32 {
+    let mut members_by_cluster: Vec<Vec<usize>> = vec![Vec::new(); k];
+    for (i, &c) in assignments.iter().enumerate() {
+        members_by_cluster[c].push(i);
+    }
+
+    let mut total = 0.0f32;
+    let mut counted = 0usize;
+
+    for (i, v) in normalized.iter().enumerate() {
+        let own = assignments[i];
+        if members_by_cluster[own].len() <= 1 {
+            // No other member of its own cluster to compare against.
+            continue;
+        }
+
+        let a: f32 = members_by_cluster[own]
+            .iter()
+            .filter(|&&j| j != i)
+            .map(|&j| cosine_distance(v, &normalized[j]))
+            .sum::<f32>()
+            / (members_by_cluster[own].len() - 1) as f32;
+
+        let b = (0..k)
+            .filter(|&c| c != own && !members_by_cluster[c].is_empty())
+            .map(|c| {
+                members_by_cluster[c].iter().map(|&j| cosine_distance(v, &normalized[j])).sum::<f32>()
+                    / members_by_cluster[c].len() as f32
+            })
+            .fold(f32::MAX, f32::min);
+
+        if b.is_finite() && b < f32::MAX {
+            let denom = a.max(b);
+            total += if denom <= f32::EPSILON { 0.0 } else { (b - a) / denom };
+            counted += 1;
+        }
+    }
+
+    if counted == 0 {
+        0.0
+    } else {
+        total / counted as f32
+    }
+}
+
+/// Sweep a small range of candidate `k` values (from sqrt(N) - 5, but at
+/// least 2, up to 20 or N - 1, whichever is smaller) and pick the one with the best mean silhouette
+/// score, so the cluster count reflects the embeddings' actual structure
+/// instead of a fixed sqrt(N) guess.
+fn auto_select_k(embeddings: &[FileEmbedding], normalized: &[Vec<f32>]) -> usize {
+    let n = embeddings.len();
+    if n < 3 {
+        return n.max(1);
+    }
+
+    let sqrt_guess = (n as f64).sqrt() as usize;
+    let lo = 2usize.max(sqrt_guess.saturating_sub(5));
+    let hi = 20usize.min(n - 1).max(lo);
+
+    let mut rng = rand::thread_rng();
+    let mut best_k = lo;
+    let mut best_score = f32::MIN;
+
+    for k in lo..=hi {
+        let (_, assignments, _) = kmeans_plusplus_once(normalized, k, &mut rng);
+        let score = silhouette_score(normalized, &assignments, k);
+        if score > best_score {
+            best_score = score;
+            best_k = k;
+        }
+    }
+
+    best_k
+}
+
+/// K-means clustering with k-means++ initialization, keeping the
+/// lowest-distortion result of `n_restarts` independent runs (see
+/// `kmeans_plusplus_once`).
+fn kmeans_plusplus_cluster(embeddings: &[FileEmbedding], k: usize, n_restarts: usize) -> Vec<Cluster> {
+    if embeddings.is_empty() || k == 0 {
+        return Vec::new();
+    }
+
+    let k = k.min(embeddings.len());
+    let normalized: Vec<Vec<f32>> = embeddings.iter().map(|e| normalize_vector(&e.embedding)).collect();
+    let mut rng = rand::thread_rng();
+
+    let mut best: Option<(Vec<Vec<f32>>, Vec<usize>, f32)> = None;
+    for _ in 0..n_restarts.max(1) {
+        let run = kmeans_plusplus_once(&normalized, k, &mut rng);
+        if best.as_ref().map(|(_, _, d)| run.2 < *d).unwrap_or(true) {
+            best = Some(run);
+        }
+    }
+    let (centroids, assignments, _) = best.expect("n_restarts.max(1) >= 1 guarantees one run");
+
+    let mut clusters = Vec::with_capacity(centroids.len());
+    for (j, centroid) in centroids.iter().enumerate() {
+        let members: Vec<usize> = (0..embeddings.len()).filter(|&i| assignments[i] == j).collect();
+        if members.is_empty() {
+            continue;
+        }
+
+        // Provisional label: the filename of whichever member's vector is
+        // closest to the final centroid.
+        let closest = members
+            .iter()
+            .copied()
+            .max_by(|&a, &b| {
+                let sim_a = dot_product(&normalized[a], centroid);
+                let sim_b = dot_product(&normalized[b], centroid);
+                sim_a.partial_cmp(&sim_b).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .expect("members is non-empty");
+
+        let label = Path::new(&embeddings[closest].path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| embeddings[closest].path.clone());
+
+        clusters.push(Cluster {
+            id: j,
+            centroid: centroid.clone(),
+            file_paths: members.iter().map(|&i| embeddings[i].path.clone()).collect(),
+            label: Some(label),
+        });
+    }
+
+    clusters
+}
+
+/// Generate a descriptive label for a cluster based on its files
+fn generate_cluster_label(file_paths: &[String]) -> String {
+    use std::collections::HashMap;
+    
+    let mut dir_counts: HashMap<String, usize> = HashMap::new();
+    let mut ext_counts: HashMap<String, usize> = HashMap::new();
+    let mut word_counts: HashMap<String, usize> = HashMap::new();
+    
+    // Common words to ignore
+    let stopwords: std::collections::HashSet<&str> = [
+        "the", "a", "an", "and", "or", "but", "in", "on", "at", "to", "for",
+        "of", "with", "by", "from", "as", "is", "was", "are", "were", "been",
+        "be", "have", "has", "had", "do", "does", "did", "will", "would", "could",
+        "should", "may", "might", "must", "shall", "can", "need", "dare", "ought",
+        "used", "index", "main", "test", "spec", "temp", "tmp", "copy", "new", "old"
+    ].iter().cloned().collect();
+    
+    for path in file_paths {
+        let path_obj = Path::new(path);
+        
+        // Count parent directories
+        if let Some(parent) = path_obj.parent() {
+            if let Some(dir_name) = parent.file_name() {
+                let dir = dir_name.to_string_lossy().to_lowercase();
+                if !dir.is_empty() && dir.len() > 1 {
+                    *dir_counts.entry(dir).or_insert(0) += 1;
+                }
+            }
+        }
+        
+        // Count extensions
+        if let Some(ext) = path_obj.extension() {
             let ext_str = ext.to_string_lossy().to_lowercase();
             *ext_counts.entry(ext_str).or_insert(0) += 1;
         }
@@ -972,6 +1582,17 @@ fn capitalize(s: &str) -> String {
     }
 }
 
+/// Scale a vector to unit length so similarity against it reduces to a
+/// plain dot product. Returns the vector unchanged if it has zero norm.
+fn normalize_vector(vector: &[f32]) -> Vec<f32> {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        vector.to_vec()
+    } else {
+        vector.iter().map(|v| v / norm).collect()
+    }
+}
+
 /// Cosine distance between two vectors (1 - cosine similarity)
 fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
     let mut dot = 0.0f32;
@@ -988,84 +1609,526 @@ fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
     1.0 - similarity
 }
 
-/// Search indexed files by query string
+/// One file's score from a single search signal (keyword or semantic),
+/// before merging. Kept separate from `SearchResult` so `merge_search_signals`
+/// can normalize and blend scores without fighting the final output shape.
+struct SignalHit {
+    path: String,
+    name: String,
+    score: f32,
+    preview: Option<String>,
+    details: Vec<ScoreDetail>,
+}
+
+/// Min-max normalize `scores` to `[0, 1]`. A list with no spread (all equal,
+/// including a single entry) normalizes to all `1.0` rather than dividing by
+/// zero.
+fn min_max_normalize(scores: &[f32]) -> Vec<f32> {
+    let min = scores.iter().cloned().fold(f32::INFINITY, f32::min);
+    let max = scores.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    let range = max - min;
+    if range <= f32::EPSILON {
+        scores.iter().map(|_| 1.0).collect()
+    } else {
+        scores.iter().map(|s| (s - min) / range).collect()
+    }
+}
+
+/// Reciprocal Rank Fusion score for a 0-based `rank` in a single ranked
+/// list, using the standard `k = 60` constant.
+fn rrf_score(rank: usize) -> f32 {
+    1.0 / (60.0 + rank as f32)
+}
+
+/// Blend a keyword-matched list and a semantic-matched list of file hits
+/// into one ranked list. A file present in both lists gets a convex
+/// combination of the two lists' independently min-max-normalized scores,
+/// `semantic_weight * sem_norm + (1 - semantic_weight) * kw_norm`. A file
+/// present in only one list falls back to that list's Reciprocal Rank
+/// Fusion score instead, since a lone normalized score from one list isn't
+/// comparable to a blended score built from both.
+fn merge_search_signals(keyword: Vec<SignalHit>, semantic: Vec<SignalHit>, semantic_weight: f32) -> Vec<SearchResult> {
+    let kw_norm = min_max_normalize(&keyword.iter().map(|h| h.score).collect::<Vec<_>>());
+    let sem_norm = min_max_normalize(&semantic.iter().map(|h| h.score).collect::<Vec<_>>());
+
+    type Signal = (f32, usize, Vec<ScoreDetail>);
+    type Entry = (String, Option<String>, Option<Signal>, Option<Signal>);
+    let mut by_path: HashMap<String, Entry> = HashMap::new();
+
+    for (rank, hit) in keyword.into_iter().enumerate() {
+        let kw_score = kw_norm[rank];
+        let entry = by_path
+            .entry(hit.path.clone())
+            .or_insert_with(|| (hit.name.clone(), hit.preview.clone(), None, None));
+        entry.2 = Some((kw_score, rank, hit.details));
+    }
+    for (rank, hit) in semantic.into_iter().enumerate() {
+        let sem_score = sem_norm[rank];
+        let entry = by_path
+            .entry(hit.path.clone())
+            .or_insert_with(|| (hit.name.clone(), hit.preview.clone(), None, None));
+        if entry.1.is_none() {
+            entry.1 = hit.preview.clone();
+        }
+        entry.3 = Some((sem_score, rank, hit.details));
+    }
+
+    let mut results: Vec<SearchResult> = by_path
+        .into_iter()
+        .map(|(path, (name, preview, kw, sem))| {
+            let mut matched_signals = Vec::new();
+            let mut score_details = Vec::new();
+            let score = match (kw, sem) {
+                (Some((kw_score, _, kw_details)), Some((sem_score, _, sem_details))) => {
+                    matched_signals.push("keyword".to_string());
+                    matched_signals.push("semantic".to_string());
+                    score_details.extend(kw_details);
+                    score_details.extend(sem_details);
+                    semantic_weight * sem_score + (1.0 - semantic_weight) * kw_score
+                }
+                (Some((_, rank, kw_details)), None) => {
+                    matched_signals.push("keyword".to_string());
+                    score_details.extend(kw_details);
+                    rrf_score(rank)
+                }
+                (None, Some((_, rank, sem_details))) => {
+                    matched_signals.push("semantic".to_string());
+                    score_details.extend(sem_details);
+                    rrf_score(rank)
+                }
+                (None, None) => unreachable!("every entry has at least one signal"),
+            };
+            SearchResult { path, name, score, preview, matched_signals, score_details }
+        })
+        .collect();
+
+    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    results
+}
+
+/// Search indexed files by query string: a keyword pass over name/path/content,
+/// blended with a semantic pass over `embeddings.json` when `semantic_weight`
+/// is above zero and an embedding provider/cache is available for this index.
+/// `semantic_weight` works the same as a typical hybrid-search knob: `1.0`
+/// ranks purely by semantic similarity, `0.0` (or no embeddings yet) degrades
+/// to the original pure-keyword behavior, and anything in between blends the
+/// two via `merge_search_signals`.
 #[tauri::command(rename_all = "camelCase")]
 pub async fn search(
     query: String,
     index_dir: String,
     top_k: usize,
-    _semantic_weight: f32,
+    semantic_weight: f32,
 ) -> Result<serde_json::Value, String> {
-    let index_path = Path::new(&index_dir);
-    let index_file = index_path.join("index.json");
-    
-    if !index_file.exists() {
-        return Err("Index not found. Please scan a directory first.".to_string());
+    let backend = index_store::configured_backend(&index_dir);
+    if !index_store::index_exists(&index_dir, &backend) {
+        return Err(AppError::IndexNotFound { index_dir: index_dir.clone() }.into());
     }
 
-    let content = fs::read_to_string(&index_file)
-        .map_err(|e| format!("Failed to read index: {}", e))?;
-    
-    let index_data: IndexData = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse index: {}", e))?;
+    let store = index_store::open_index_store_for(&index_dir)?;
+    let files = store.get_files()?;
 
     let query_lower = query.to_lowercase();
-    let mut results: Vec<SearchResult> = Vec::new();
+    let mut keyword_hits: Vec<SignalHit> = Vec::new();
 
-    for file in &index_data.files {
+    for file in &files {
         let name_lower = file.name.to_lowercase();
         let path_lower = file.path.to_lowercase();
-        
+
         // Simple text matching score
         let mut score: f32 = 0.0;
-        
+        let mut details: Vec<ScoreDetail> = Vec::new();
+
         if name_lower.contains(&query_lower) {
             score += 1.0;
+            details.push(ScoreDetail::KeywordName { weight: 1.0, matched: true });
         }
         if path_lower.contains(&query_lower) {
             score += 0.5;
+            details.push(ScoreDetail::KeywordPath { weight: 0.5, matched: true });
         }
 
-        // Try to search within file content
-        if let Ok(content) = fs::read_to_string(&file.path) {
-            if content.to_lowercase().contains(&query_lower) {
+        // Try to search within file content (or a CSV/JSONL record's row)
+        let mut preview = None;
+        if let Ok(content) = read_entry_content(file) {
+            let content_lower = content.to_lowercase();
+            let match_count = content_lower.matches(&query_lower).count();
+            if match_count > 0 {
                 score += 0.8;
-                
-                // Get a preview snippet
-                let content_lower = content.to_lowercase();
+
+                // Get a preview snippet and the byte offsets of the match
+                // it was built from, so the frontend can highlight it.
+                let mut match_start = None;
+                let mut match_end = None;
                 if let Some(pos) = content_lower.find(&query_lower) {
                     let start = pos.saturating_sub(50);
                     let end = (pos + query.len() + 50).min(content.len());
-                    let preview = &content[start..end];
-                    
-                    if score > 0.0 {
-                        results.push(SearchResult {
-                            path: file.path.clone(),
-                            name: file.name.clone(),
-                            score,
-                            preview: Some(preview.trim().to_string()),
-                        });
+                    preview = Some(content[start..end].trim().to_string());
+                    match_start = Some(pos);
+                    match_end = Some(pos + query.len());
+                }
+                details.push(ScoreDetail::KeywordContent { matched: true, match_count, match_start, match_end });
+            }
+        }
+
+        if score > 0.0 {
+            keyword_hits.push(SignalHit { path: file.path.clone(), name: file.name.clone(), score, preview, details });
+        }
+    }
+    keyword_hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+    // Degrade gracefully to pure keyword search unless the caller asked for
+    // a semantic contribution and there's a configured provider and cached
+    // embeddings to supply it.
+    let config_file = Path::new(&index_dir).join("azure_config.json");
+    let semantic_hits: Vec<SignalHit> = if semantic_weight > 0.0
+        && config_file.exists()
+        && index_store::embeddings_exist(&index_dir, &backend)
+    {
+        let config_content = fs::read_to_string(&config_file)
+            .map_err(|e| format!("Failed to read config: {}", e))?;
+        let config: AzureConfig = serde_json::from_str(&config_content)
+            .map_err(|e| format!("Failed to parse config: {}", e))?;
+        let provider = embedding_provider::build_provider(&config)?;
+        let query_vectors = provider.embed(std::slice::from_ref(&query)).await?;
+        let query_vector = &query_vectors[0];
+
+        let embeddings = store.get_embeddings()?;
+
+        // When a vector index has been built (`build_vector_index`), narrow
+        // the scan down to its approximate nearest neighbors instead of
+        // walking every embedding; `vector_index` ids are positions into
+        // this same `embeddings` vec, since that's how `build_vector_index`
+        // built it. Falls back to a full scan when no index exists yet.
+        let candidate_ids: Option<Vec<usize>> = if vector_index::vector_index_exists(&index_dir) {
+            vector_index::load_index(&index_dir)
+                .ok()
+                .map(|idx| idx.search(query_vector, (top_k * 5).max(50), 100).into_iter().map(|(id, _)| id).collect())
+        } else {
+            None
+        };
+        let candidates: Box<dyn Iterator<Item = &FileEmbedding>> = match &candidate_ids {
+            Some(ids) => Box::new(ids.iter().filter_map(|&id| embeddings.get(id))),
+            None => Box::new(embeddings.iter()),
+        };
+
+        // A file can have multiple chunks; keep only each file's
+        // best-matching chunk, both for its score and its preview snippet.
+        let mut best_by_path: HashMap<String, (f32, &FileEmbedding)> = HashMap::new();
+        for emb in candidates {
+            if emb.embedding.len() != query_vector.len() {
+                continue; // Stale cache from a different provider/model; skip rather than panic
+            }
+            let similarity = 1.0 - cosine_distance(query_vector, &emb.embedding);
+            best_by_path
+                .entry(emb.path.clone())
+                .and_modify(|(best_score, best_emb)| {
+                    if similarity > *best_score {
+                        *best_score = similarity;
+                        *best_emb = emb;
                     }
-                    continue;
+                })
+                .or_insert((similarity, emb));
+        }
+
+        let mut hits: Vec<SignalHit> = best_by_path
+            .into_values()
+            .map(|(score, emb)| {
+                let name = Path::new(&emb.path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| emb.path.clone());
+                let preview = fs::read_to_string(&emb.path).ok().map(|content| {
+                    let range_valid = emb.end_byte > emb.start_byte
+                        && emb.end_byte <= content.len()
+                        && content.is_char_boundary(emb.start_byte)
+                        && content.is_char_boundary(emb.end_byte);
+                    if range_valid {
+                        content[emb.start_byte..emb.end_byte].to_string()
+                    } else {
+                        content.chars().take(200).collect()
+                    }
+                });
+                SignalHit { path: emb.path.clone(), name, score, preview, details: Vec::new() }
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        // Rank is the hit's 0-based position in this sorted list, so it has
+        // to be filled in after sorting rather than while building `hits`.
+        for (rank, hit) in hits.iter_mut().enumerate() {
+            hit.details.push(ScoreDetail::Semantic { similarity: hit.score, rank });
+        }
+        hits
+    } else {
+        Vec::new()
+    };
+
+    let mut results = merge_search_signals(keyword_hits, semantic_hits, semantic_weight);
+    let dropped = results.len().saturating_sub(top_k);
+    results.truncate(top_k);
+
+    let mut response = serde_json::json!({ "results": results });
+    if dropped > 0 {
+        response["ranking_score_threshold"] = serde_json::Value::String(format!(
+            "{} lower-scoring result(s) beyond top_k={} were dropped",
+            dropped, top_k
+        ));
+    }
+
+    Ok(response)
+}
+
+/// Build (or rebuild) the HNSW approximate-nearest-neighbor index over this
+/// index's current embeddings, persisted as `vector_index.bin`. Run this
+/// after `generate_embeddings`; `search`/`query_vectors` pick it up
+/// automatically once it exists. Below `vector_index::MIN_VECTORS_FOR_INDEX`
+/// embeddings, brute force is cheap enough that no index is built at all.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn build_vector_index(index_dir: String) -> Result<serde_json::Value, String> {
+    let backend = index_store::configured_backend(&index_dir);
+    if !index_store::embeddings_exist(&index_dir, &backend) {
+        return Err("Embeddings not found. Please generate embeddings first.".to_string());
+    }
+
+    let store = index_store::open_index_store_for(&index_dir)?;
+    let embeddings = store.get_embeddings()?;
+
+    if embeddings.len() < vector_index::MIN_VECTORS_FOR_INDEX {
+        return Ok(serde_json::json!({
+            "built": false,
+            "vector_count": embeddings.len(),
+            "message": format!(
+                "Only {} embeddings; brute-force search is fast enough below {}, so no index was built.",
+                embeddings.len(),
+                vector_index::MIN_VECTORS_FOR_INDEX
+            ),
+        }));
+    }
+
+    let vectors: Vec<Vec<f32>> = embeddings.iter().map(|e| e.embedding.clone()).collect();
+    // Seeded from the vector count so a rebuild over the same embeddings is
+    // reproducible; an actual new embedding run changes the count (or the
+    // vectors) and so gets a different layer assignment, which is fine.
+    let index = HnswIndex::build(vectors, HnswParams::default(), embeddings.len() as u64);
+    vector_index::save_index(&index_dir, &index)?;
+
+    Ok(serde_json::json!({
+        "built": true,
+        "vector_count": embeddings.len(),
+    }))
+}
+
+/// Query the persisted vector index (or fall back to a brute-force scan if
+/// none has been built yet) for the `top_k` embeddings closest to
+/// `query_embedding` by cosine similarity. The same index lookup `search`
+/// does for its semantic pass, exposed directly so the frontend can offer a "similar files" action from
+/// an arbitrary embedding without going through a text query.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn query_vectors(index_dir: String, query_embedding: Vec<f32>, top_k: usize) -> Result<serde_json::Value, String> {
+    let store = index_store::open_index_store_for(&index_dir)?;
+    let embeddings = store.get_embeddings()?;
+
+    let neighbors: Vec<(usize, f32)> = if vector_index::vector_index_exists(&index_dir) {
+        let index = vector_index::load_index(&index_dir)?;
+        index.search(&query_embedding, top_k, 100)
+    } else {
+        let vectors: Vec<Vec<f32>> = embeddings.iter().map(|e| e.embedding.clone()).collect();
+        vector_index::brute_force_search(&vectors, &query_embedding, top_k)
+    };
+
+    let results: Vec<serde_json::Value> = neighbors
+        .into_iter()
+        .filter_map(|(id, distance)| {
+            embeddings.get(id).map(|emb| {
+                serde_json::json!({
+                    "path": emb.path,
+                    "chunkIndex": emb.chunk_index,
+                    "similarity": 1.0 - distance,
+                })
+            })
+        })
+        .collect();
+
+    Ok(serde_json::to_value(results).unwrap())
+}
+
+/// The embedding template configured for this index (or the default
+/// whole-content template if none has been saved yet).
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_embedding_template(index_dir: String) -> Result<serde_json::Value, String> {
+    Ok(serde_json::to_value(embedding_template::get_template(&index_dir)).unwrap())
+}
+
+/// Save the embedding template for this index. Takes effect on the next
+/// `generate_embeddings` run; it does not retroactively re-render already
+/// cached embeddings.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn save_embedding_template(index_dir: String, template: String) -> Result<(), String> {
+    embedding_template::save_template(&index_dir, &embedding_template::EmbeddingTemplate { template })
+}
+
+/// Render `template` against one indexed file (`sample_path`, or the first
+/// indexed file if omitted) without saving it, so the frontend can preview
+/// what will actually get embedded and catch a typo'd `{{ field }}` before
+/// committing to a full `generate_embeddings` run.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn validate_embedding_template(
+    index_dir: String,
+    template: String,
+    sample_path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let store = index_store::open_index_store_for(&index_dir)?;
+    let files = store.get_files()?;
+
+    let sample = match &sample_path {
+        Some(path) => files
+            .iter()
+            .find(|f| &f.path == path)
+            .ok_or_else(|| format!("No indexed file found at path: {}", path))?,
+        None => files
+            .first()
+            .ok_or_else(|| "No indexed files to preview against.".to_string())?,
+    };
+
+    let content = read_entry_content(sample)?;
+    let report = embedding_template::render(&template, sample, &content);
+
+    Ok(serde_json::json!({
+        "sample_path": sample.path,
+        "rendered_preview": report.rendered.chars().take(500).collect::<String>(),
+        "unknown_fields": report.unknown_fields,
+    }))
+}
+
+/// Embed `query` through the configured provider and rank stored
+/// `FileEmbedding`s against it by cosine similarity. Both the query vector
+/// and every stored vector are normalized to unit length up front, so
+/// similarity reduces to a plain dot product; a bounded min-heap keeps
+/// only the current top `top_k` instead of sorting the whole corpus.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn semantic_search(index_dir: String, query: String, top_k: usize) -> Result<serde_json::Value, String> {
+    let index_path = Path::new(&index_dir);
+    let config_file = index_path.join("azure_config.json");
+
+    if !config_file.exists() {
+        return Err("Azure config not found. Please configure an embedding provider first.".to_string());
+    }
+
+    let backend = index_store::configured_backend(&index_dir);
+    if !index_store::embeddings_exist(&index_dir, &backend) {
+        return Err("Embeddings not found. Please generate embeddings first.".to_string());
+    }
+
+    let config_content = fs::read_to_string(&config_file)
+        .map_err(|e| format!("Failed to read config: {}", e))?;
+    let config: AzureConfig = serde_json::from_str(&config_content)
+        .map_err(|e| format!("Failed to parse config: {}", e))?;
+    let provider = embedding_provider::build_provider(&config)?;
+
+    let store = index_store::open_index_store_for(&index_dir)?;
+    let embeddings = store.get_embeddings()?;
+
+    if embeddings.is_empty() {
+        return Err("No embeddings found. Please generate embeddings first.".to_string());
+    }
+
+    let query_vectors = provider.embed(std::slice::from_ref(&query)).await?;
+    let query_vector = normalize_vector(&query_vectors[0]);
+
+    // Min-heap keyed by similarity so the smallest of the current top-k is always evictable
+    let mut heap: BinaryHeap<Reverse<(OrderedFloat<f32>, usize)>> = BinaryHeap::new();
+    let k = top_k.max(1);
+
+    for (i, emb) in embeddings.iter().enumerate() {
+        if emb.embedding.len() != query_vector.len() {
+            continue; // Stale cache from a different provider/model; skip rather than panic
+        }
+        let stored = normalize_vector(&emb.embedding);
+        let similarity: f32 = query_vector.iter().zip(stored.iter()).map(|(a, b)| a * b).sum();
+
+        if heap.len() < k {
+            heap.push(Reverse((OrderedFloat(similarity), i)));
+        } else if let Some(Reverse((min_sim, _))) = heap.peek()
+            && similarity > min_sim.into_inner()
+        {
+            heap.pop();
+            heap.push(Reverse((OrderedFloat(similarity), i)));
+        }
+    }
+
+    let mut scored: Vec<(f32, usize)> = heap
+        .into_iter()
+        .map(|Reverse((sim, i))| (sim.into_inner(), i))
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    let results: Vec<SearchResult> = scored
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (score, i))| {
+            let emb = &embeddings[i];
+            let name = Path::new(&emb.path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| emb.path.clone());
+
+            let preview = fs::read_to_string(&emb.path).ok().map(|content| {
+                let range_valid = emb.end_byte > emb.start_byte
+                    && emb.end_byte <= content.len()
+                    && content.is_char_boundary(emb.start_byte)
+                    && content.is_char_boundary(emb.end_byte);
+
+                if range_valid {
+                    content[emb.start_byte..emb.end_byte].to_string()
+                } else {
+                    content.chars().take(200).collect()
                 }
-            }
-        }
+            });
 
-        if score > 0.0 {
-            results.push(SearchResult {
-                path: file.path.clone(),
-                name: file.name.clone(),
+            SearchResult {
+                path: emb.path.clone(),
+                name,
                 score,
-                preview: None,
-            });
-        }
+                preview,
+                matched_signals: vec!["semantic".to_string()],
+                score_details: vec![ScoreDetail::Semantic { similarity: score, rank }],
+            }
+        })
+        .collect();
+
+    Ok(serde_json::to_value(results).unwrap_or_default())
+}
+
+/// Embed `query` through the configured provider and rank it against the
+/// shared Postgres/pgvector `embeddings` table rather than the per-index
+/// `embeddings.json`, so searches can be served against a store that
+/// spans sessions and indexes. Gated behind the `pgvector` feature; the
+/// connection string comes from `DATABASE_URL`, matching the convention
+/// `storage_backend::PostgresStorageBackend` already uses.
+#[cfg(feature = "pgvector")]
+#[tauri::command(rename_all = "camelCase")]
+pub async fn search_embeddings(index_dir: String, query: String, k: usize) -> Result<serde_json::Value, String> {
+    let index_path = Path::new(&index_dir);
+    let config_file = index_path.join("azure_config.json");
+
+    if !config_file.exists() {
+        return Err("Azure config not found. Please configure an embedding provider first.".to_string());
     }
 
-    // Sort by score descending and take top_k
-    results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-    results.truncate(top_k);
+    let config_content = fs::read_to_string(&config_file)
+        .map_err(|e| format!("Failed to read config: {}", e))?;
+    let config: AzureConfig = serde_json::from_str(&config_content)
+        .map_err(|e| format!("Failed to parse config: {}", e))?;
+    let provider = embedding_provider::build_provider(&config)?;
 
-    Ok(serde_json::to_value(results).unwrap())
+    let database_url = std::env::var("DATABASE_URL")
+        .map_err(|_| "DATABASE_URL must be set to use pgvector search".to_string())?;
+    let store = crate::vector_store::PgVectorStore::connect(&database_url, provider.dimensions()).await?;
+
+    let query_vectors = provider.embed(std::slice::from_ref(&query)).await?;
+    let hits = store.search(&query_vectors[0], k.max(1)).await?;
+
+    Ok(serde_json::to_value(hits).unwrap_or_default())
 }
 
 /// Get summary of clusters
@@ -1302,6 +2365,7 @@ pub async fn save_azure_config(
     api_key: String,
     deployment_name: String,
     api_version: Option<String>,
+    provider: Option<String>,
 ) -> Result<serde_json::Value, String> {
     println!("[RUST] save_azure_config called");
     
@@ -1331,6 +2395,7 @@ pub async fn save_azure_config(
         api_key: final_api_key,
         deployment_name,
         api_version: api_version.unwrap_or_else(|| "2024-02-01".to_string()),
+        provider: provider.unwrap_or_else(default_embedding_provider),
     };
     
     let json = serde_json::to_string_pretty(&config)
@@ -1356,141 +2421,68 @@ pub async fn load_azure_config(index_dir: String) -> Result<serde_json::Value, S
             "configured": false,
             "endpoint": "",
             "deployment_name": "",
-            "api_version": "2024-02-01"
+            "api_version": "2024-02-01",
+            "provider": default_embedding_provider()
         }));
     }
-    
+
     let content = fs::read_to_string(&config_file)
         .map_err(|e| format!("Failed to read config: {}", e))?;
-    
+
     let config: AzureConfig = serde_json::from_str(&content)
         .map_err(|e| format!("Failed to parse config: {}", e))?;
-    
+
     Ok(serde_json::json!({
-        "configured": !config.api_key.is_empty(),
+        "configured": !config.api_key.is_empty() || config.provider == "ollama",
         "endpoint": config.endpoint,
         "deployment_name": config.deployment_name,
         "api_version": config.api_version,
+        "provider": config.provider,
         "has_key": !config.api_key.is_empty()
     }))
 }
 
-/// Validate Azure configuration by making a small embeddings request
+/// Which `IndexStore` backend (`"json"` or `"sqlite"`) this index is configured to use.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_storage_backend(index_dir: String) -> Result<String, String> {
+    Ok(index_store::configured_backend(&index_dir))
+}
+
+/// Select the `IndexStore` backend (`"json"` or `"sqlite"`) this index should
+/// use from now on. Switching backends does not migrate existing data
+/// between them.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn set_storage_backend(index_dir: String, backend: String) -> Result<(), String> {
+    // Fail fast on an unrecognized backend rather than silently persisting
+    // a config that `open_index_store` would later reject.
+    index_store::open_index_store(&index_dir, &backend)?;
+    index_store::set_configured_backend(&index_dir, &backend)
+}
+
+/// Validate an embedding provider configuration by making a small test
+/// request through it. Dispatches to whichever provider `provider` names
+/// ("azure", "openai", "ollama", or "local"), defaulting to "azure" for
+/// existing callers that predate the `provider` parameter.
 #[tauri::command(rename_all = "camelCase")]
 pub async fn validate_azure_config(
-    index_dir: String,
+    _index_dir: String,
     endpoint: String,
     api_key: String,
     deployment_name: String,
     api_version: Option<String>,
+    provider: Option<String>,
 ) -> Result<serde_json::Value, String> {
-    println!("[RUST] validate_azure_config called for endpoint: {}", endpoint);
-
-    // Normalize endpoint
-    let mut base = endpoint.trim_end_matches('/').to_string();
-    let mut suggested: Option<String> = None;
-
-    if base.contains("/api/projects") || base.contains("/api/") {
-        // Try to extract host and suggest cognitiveservices domain
-        if let Ok(url) = reqwest::Url::parse(&base) {
-            if let Some(host) = url.host_str() {
-                if host.contains("services.ai.azure.com") {
-                    if let Some(prefix) = host.split('.').next() {
-                        suggested = Some(format!("https://{}.cognitiveservices.azure.com", prefix));
-                    }
-                } else {
-                    // Suggest base host only
-                    suggested = Some(format!("https://{}", host));
-                }
-            }
-        }
-    } else if base.contains("services.ai.azure.com") {
-        // If user supplied services.ai.azure.com, suggest cognitiveservices
-        if let Ok(url) = reqwest::Url::parse(&base) {
-            if let Some(host) = url.host_str() {
-                if let Some(prefix) = host.split('.').next() {
-                    suggested = Some(format!("https://{}.cognitiveservices.azure.com", prefix));
-                }
-            }
-        }
-    }
-
-    // Prepare versions to try
-    let mut tried_versions: Vec<String> = Vec::new();
-    let mut api_version_current = api_version.unwrap_or_else(|| "2024-02-01".to_string());
-    let fallback_versions = vec!["2024-02-01".to_string(), "2023-10-01".to_string(), "2023-05-15".to_string()];
-
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(8))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    // Try current and fallbacks
-    for v in std::iter::once(api_version_current.clone()).chain(fallback_versions.into_iter()) {
-        if tried_versions.contains(&v) { continue; }
-        tried_versions.push(v.clone());
-
-        // Ensure base has /openai path
-        let mut url_base = base.clone();
-        if !url_base.ends_with("/openai") && !url_base.ends_with("/openai/") {
-            url_base = format!("{}/openai", url_base);
-        }
-
-        let url = format!("{}/deployments/{}/embeddings?api-version={}", url_base, deployment_name, v);
-
-        println!("[RUST] validate attempt url: {}", url);
-
-        let body = serde_json::json!({ "input": ["healthcheck"] });
-
-        match client.post(&url).header("api-key", &api_key).json(&body).send().await {
-            Ok(response) => {
-                let status = response.status().as_u16();
-                if response.status().is_success() {
-                    // Good response - success
-                    return Ok(serde_json::json!({
-                        "success": true,
-                        "message": "Validation succeeded",
-                        "tried_versions": tried_versions,
-                        "final_url": url,
-                        "status_code": status
-                    }));
-                } else {
-                    let text = response.text().await.unwrap_or_default();
-                    // If api-version not supported, try next
-                    if text.contains("API version not supported") {
-                        println!("[RUST] API version not supported for {}", v);
-                        continue;
-                    }
-                    // Return error details
-                    return Ok(serde_json::json!({
-                        "success": false,
-                        "message": format!("Server returned {}: {}", status, text),
-                        "tried_versions": tried_versions,
-                        "final_url": url,
-                        "status_code": status,
-                        "suggested_endpoint": suggested
-                    }));
-                }
-            }
-            Err(e) => {
-                println!("[RUST] Request error: {}", e);
-                // network or connection error - return as failure but include suggestion
-                return Ok(serde_json::json!({
-                    "success": false,
-                    "message": format!("Request failed: {}", e),
-                    "tried_versions": tried_versions,
-                    "suggested_endpoint": suggested
-                }));
-            }
-        }
-    }
+    let config = AzureConfig {
+        endpoint,
+        api_key,
+        deployment_name,
+        api_version: api_version.unwrap_or_else(|| "2024-02-01".to_string()),
+        provider: provider.unwrap_or_else(default_embedding_provider),
+    };
+    println!("[RUST] validate_azure_config called for provider {} endpoint: {}", config.provider, config.endpoint);
 
-    Ok(serde_json::json!({
-        "success": false,
-        "message": "All tried API versions failed",
-        "tried_versions": tried_versions,
-        "suggested_endpoint": suggested
-    }))
+    let provider = embedding_provider::build_provider(&config)?;
+    provider.validate().await
 }
 
 
@@ -1600,6 +2592,8 @@ pub async fn delete_duplicate_files(file_paths: Vec<String>) -> Result<serde_jso
         }
     }
     
+    metrics::counter!("md_scanner_duplicate_files_deleted_total").increment(deleted as u64);
+
     Ok(serde_json::json!({
         "success": errors.is_empty(),
         "deleted": deleted,
@@ -1612,29 +2606,40 @@ pub async fn delete_duplicate_files(file_paths: Vec<String>) -> Result<serde_jso
 // ============================================================================
 
 use crate::file_intelligence::{
-    self, DiscoveredDocument, UserPreferences,
+    self, SuggestionRule,
 };
 use std::sync::Mutex;
 use once_cell::sync::Lazy;
 
-// Global state for user preferences (will be replaced with SQLite later)
-static USER_PREFS: Lazy<Mutex<UserPreferences>> = Lazy::new(|| Mutex::new(UserPreferences::default()));
-static LAST_SCAN: Lazy<Mutex<Vec<DiscoveredDocument>>> = Lazy::new(|| Mutex::new(Vec::new()));
-
-/// Scan a directory for organizable documents
+/// Scan a directory for organizable documents. `include`/`exclude` are
+/// gitignore-style glob patterns (e.g. `**/*.pdf`, `**/node_modules/**`);
+/// excludes prune matched directories from the walk entirely and win over
+/// includes on conflict. The scan is persisted via `storage_backend`,
+/// keyed by `index_dir`, so `get_organization_suggestions`/
+/// `get_scan_statistics` against the same index see it on a later run too.
 #[tauri::command(rename_all = "camelCase")]
-pub async fn scan_for_documents(root_path: String, max_depth: Option<usize>) -> Result<serde_json::Value, String> {
+pub async fn scan_for_documents(
+    index_dir: String,
+    root_path: String,
+    max_depth: Option<usize>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+) -> Result<serde_json::Value, String> {
     println!("[FILE_INTEL] scan_for_documents: {}", root_path);
-    
-    let documents = file_intelligence::scan_for_documents(&root_path, max_depth)?;
-    
-    // Store for later use
-    if let Ok(mut scan) = LAST_SCAN.lock() {
-        *scan = documents.clone();
-    }
-    
+    metrics::counter!("md_scanner_scan_invocations_total").increment(1);
+
+    let documents = file_intelligence::scan_for_documents(
+        &root_path,
+        max_depth,
+        &include.unwrap_or_default(),
+        &exclude.unwrap_or_default(),
+    )?;
+
+    storage_backend::open()?.save_last_scan(&index_dir, &documents)?;
+
     let count = documents.len();
-    
+    metrics::counter!("md_scanner_scan_documents_found_total").increment(count as u64);
+
     Ok(serde_json::json!({
         "success": true,
         "document_count": count,
@@ -1642,19 +2647,92 @@ pub async fn scan_for_documents(root_path: String, max_depth: Option<usize>) ->
     }))
 }
 
-/// Get organization suggestions based on last scan
+/// Enqueue a directory scan on the background worker thread instead of
+/// blocking the invoking call until the whole tree is walked. Returns the
+/// new `Task` immediately; the frontend polls `get_task` for progress
+/// (`details.documents_found`) and, once its status is `Succeeded`, calls
+/// `get_organization_suggestions`/`get_scan_statistics` the same way a
+/// `scan_for_documents` caller already does. Fails immediately if the
+/// worker queue is already full; the task record created for the scan is
+/// then marked `Failed`.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn enqueue_scan(
+    index_dir: String,
+    root_path: String,
+    max_depth: Option<usize>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+) -> Result<serde_json::Value, String> {
+    let task = task_store::enqueue_task(&index_dir, "scan", serde_json::json!({ "documents_found": 0 }))?;
+    let task_id = task.id.clone();
+
+    let (job_index_dir, job_task_id) = (index_dir.clone(), task_id.clone());
+    if let Err(e) = task_queue::enqueue(move || run_scan_task(job_index_dir, job_task_id, root_path, max_depth, include, exclude)) {
+        let _ = task_store::finish_task(&index_dir, &task_id, false, serde_json::json!({ "error": e }));
+        return Err(e);
+    }
+
+    serde_json::to_value(&task).map_err(|e| format!("Failed to serialize task: {}", e))
+}
+
+/// Body of an `enqueue_scan` job, run on the background worker thread:
+/// marks the task `Processing`, walks the tree reporting progress and
+/// checking for cancellation as it goes, persists the result via
+/// `storage_backend` on success, and marks the task finished either way.
+fn run_scan_task(
+    index_dir: String,
+    task_id: String,
+    root_path: String,
+    max_depth: Option<usize>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+) {
+    let Ok(_) = task_store::start_task(&index_dir, &task_id) else { return };
+    metrics::counter!("md_scanner_scan_invocations_total").increment(1);
+
+    let on_progress = |found: usize| {
+        let _ = task_store::update_task_details(&index_dir, &task_id, serde_json::json!({ "documents_found": found }));
+    };
+    let cancel = || task_store::is_canceled(&task_id);
+
+    let result = file_intelligence::scan_for_documents_with_progress(
+        &root_path,
+        max_depth,
+        &include.unwrap_or_default(),
+        &exclude.unwrap_or_default(),
+        Some(&on_progress),
+        Some(&cancel),
+    );
+
+    match result {
+        Ok(documents) => {
+            let count = documents.len();
+            metrics::counter!("md_scanner_scan_documents_found_total").increment(count as u64);
+            let saved = storage_backend::open().and_then(|b| b.save_last_scan(&index_dir, &documents));
+            match saved {
+                Ok(()) => {
+                    let _ = task_store::finish_task(&index_dir, &task_id, true, serde_json::json!({ "documents_found": count }));
+                }
+                Err(e) => {
+                    let _ = task_store::finish_task(&index_dir, &task_id, false, serde_json::json!({ "error": e }));
+                }
+            }
+        }
+        Err(e) => {
+            let _ = task_store::finish_task(&index_dir, &task_id, false, serde_json::json!({ "error": e }));
+        }
+    }
+}
+
+/// Get organization suggestions based on this index's last scan
 #[tauri::command(rename_all = "camelCase")]
-pub async fn get_organization_suggestions() -> Result<serde_json::Value, String> {
+pub async fn get_organization_suggestions(index_dir: String) -> Result<serde_json::Value, String> {
     println!("[FILE_INTEL] get_organization_suggestions");
-    
-    let documents = LAST_SCAN.lock()
-        .map_err(|e| format!("Lock error: {}", e))?
-        .clone();
-    
-    let prefs = USER_PREFS.lock()
-        .map_err(|e| format!("Lock error: {}", e))?
-        .clone();
-    
+
+    let backend = storage_backend::open()?;
+    let documents = backend.get_last_scan(&index_dir)?;
+    let prefs = backend.get_preferences(&index_dir)?;
+
     if documents.is_empty() {
         return Ok(serde_json::json!({
             "success": true,
@@ -1662,9 +2740,9 @@ pub async fn get_organization_suggestions() -> Result<serde_json::Value, String>
             "message": "No documents scanned yet. Run scan_for_documents first."
         }));
     }
-    
+
     let suggestions = file_intelligence::generate_suggestions(&documents, &prefs);
-    
+
     Ok(serde_json::json!({
         "success": true,
         "suggestion_count": suggestions.len(),
@@ -1672,25 +2750,23 @@ pub async fn get_organization_suggestions() -> Result<serde_json::Value, String>
     }))
 }
 
-/// Get statistics about the scanned documents
+/// Get statistics about this index's last scanned documents
 #[tauri::command(rename_all = "camelCase")]
-pub async fn get_scan_statistics() -> Result<serde_json::Value, String> {
+pub async fn get_scan_statistics(index_dir: String) -> Result<serde_json::Value, String> {
     println!("[FILE_INTEL] get_scan_statistics");
-    
-    let documents = LAST_SCAN.lock()
-        .map_err(|e| format!("Lock error: {}", e))?
-        .clone();
-    
+
+    let documents = storage_backend::open()?.get_last_scan(&index_dir)?;
+
     if documents.is_empty() {
         return Ok(serde_json::json!({
             "success": false,
             "message": "No documents scanned yet"
         }));
     }
-    
+
     let stats = file_intelligence::calculate_statistics(&documents);
     let patterns = file_intelligence::detect_naming_patterns(&documents);
-    
+
     Ok(serde_json::json!({
         "success": true,
         "statistics": stats,
@@ -1700,61 +2776,169 @@ pub async fn get_scan_statistics() -> Result<serde_json::Value, String> {
 
 /// Dismiss a suggestion (don't suggest this file again)
 #[tauri::command(rename_all = "camelCase")]
-pub async fn dismiss_suggestion(file_path: String) -> Result<serde_json::Value, String> {
+pub async fn dismiss_suggestion(index_dir: String, file_path: String) -> Result<serde_json::Value, String> {
     println!("[FILE_INTEL] dismiss_suggestion: {}", file_path);
-    
-    let mut prefs = USER_PREFS.lock()
-        .map_err(|e| format!("Lock error: {}", e))?;
-    
+
+    let backend = storage_backend::open()?;
+    let mut prefs = backend.get_preferences(&index_dir)?;
     prefs.dismissed_suggestions.push(file_path.clone());
-    
+    backend.save_preferences(&index_dir, &prefs)?;
+
     Ok(serde_json::json!({
         "success": true,
         "dismissed": file_path
     }))
 }
 
+/// List the registered suggestion rules, in priority (confidence) order
+#[tauri::command(rename_all = "camelCase")]
+pub async fn list_suggestion_rules(index_dir: String) -> Result<serde_json::Value, String> {
+    let mut rules = storage_backend::open()?.get_preferences(&index_dir)?.rules;
+
+    rules.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+
+    Ok(serde_json::json!({
+        "success": true,
+        "rules": rules
+    }))
+}
+
+/// Register a new suggestion rule (or overwrite one with the same name)
+#[tauri::command(rename_all = "camelCase")]
+pub async fn add_suggestion_rule(index_dir: String, rule: SuggestionRule) -> Result<serde_json::Value, String> {
+    println!("[FILE_INTEL] add_suggestion_rule: {}", rule.name);
+
+    let backend = storage_backend::open()?;
+    let mut prefs = backend.get_preferences(&index_dir)?;
+    prefs.rules.retain(|r| r.name != rule.name);
+    prefs.rules.push(rule.clone());
+    backend.save_preferences(&index_dir, &prefs)?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "rule": rule
+    }))
+}
+
+/// Remove a registered suggestion rule by name (built-in or user-added)
+#[tauri::command(rename_all = "camelCase")]
+pub async fn remove_suggestion_rule(index_dir: String, name: String) -> Result<serde_json::Value, String> {
+    println!("[FILE_INTEL] remove_suggestion_rule: {}", name);
+
+    let backend = storage_backend::open()?;
+    let mut prefs = backend.get_preferences(&index_dir)?;
+    let before = prefs.rules.len();
+    prefs.rules.retain(|r| r.name != name);
+    let removed = before != prefs.rules.len();
+    backend.save_preferences(&index_dir, &prefs)?;
+
+    Ok(serde_json::json!({
+        "success": removed,
+        "removed": name
+    }))
+}
+
 // ============================================================================
 // FILE WATCHER COMMANDS
 // ============================================================================
 
-use crate::file_watcher::{FileWatcher, WatchConfig, FileEvent};
+use crate::file_watcher::{FileWatcher, WatchConfig, WatchHandle, FileEvent};
 
 static FILE_WATCHER: Lazy<Mutex<Option<FileWatcher>>> = Lazy::new(|| Mutex::new(None));
+static WATCH_HANDLE: Lazy<Mutex<Option<WatchHandle>>> = Lazy::new(|| Mutex::new(None));
 static WATCHER_EVENTS: Lazy<Mutex<Vec<FileEvent>>> = Lazy::new(|| Mutex::new(Vec::new()));
 
-/// Start the file watcher
+/// How many watcher events to keep in `index_dir`'s on-disk ring buffer.
+const MAX_WATCHER_EVENTS: usize = 2000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct WatcherEventLog {
+    entries: Vec<FileEvent>,
+    last_updated: String,
+}
+
+fn watcher_event_log_path(index_dir: &str) -> std::path::PathBuf {
+    Path::new(index_dir).join("watcher_events.json")
+}
+
+fn load_watcher_event_log(index_dir: &str) -> WatcherEventLog {
+    fs::read_to_string(watcher_event_log_path(index_dir))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Append `event` to `index_dir`'s on-disk ring buffer, capped at
+/// `MAX_WATCHER_EVENTS` the same way `log_error`'s `error_log.json` is, so a
+/// long-running watcher's history survives a restart without growing
+/// unbounded.
+fn persist_watcher_event(index_dir: &str, event: &FileEvent) {
+    let mut log = load_watcher_event_log(index_dir);
+    log.entries.push(event.clone());
+    if log.entries.len() > MAX_WATCHER_EVENTS {
+        log.entries = log.entries.split_off(log.entries.len() - MAX_WATCHER_EVENTS);
+    }
+    log.last_updated = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    if let Ok(json) = serde_json::to_string_pretty(&log) {
+        let _ = fs::write(watcher_event_log_path(index_dir), json);
+    }
+}
+
+/// Start the file watcher, persisting its coalesced event log under
+/// `index_dir`.
 #[tauri::command(rename_all = "camelCase")]
-pub async fn start_file_watcher(watch_paths: Option<Vec<String>>) -> Result<serde_json::Value, String> {
+pub async fn start_file_watcher(
+    index_dir: String,
+    watch_paths: Option<Vec<String>>,
+    include_patterns: Option<Vec<String>>,
+    exclude_patterns: Option<Vec<String>>,
+) -> Result<serde_json::Value, String> {
     println!("[FILE_WATCHER] start_file_watcher");
-    
+
     let mut config = WatchConfig::default();
     if let Some(paths) = watch_paths {
         config.paths = paths;
     }
-    
+    if let Some(include) = include_patterns {
+        config.include_patterns = include;
+    }
+    if let Some(exclude) = exclude_patterns {
+        config.exclude_patterns = exclude;
+    }
+
     let mut watcher = FileWatcher::new(config.clone());
-    let rx = watcher.start()?;
-    
-    // Store the watcher
+    let (rx, handle) = watcher.start()?;
+
+    // Store the watcher and its live handle
     {
         let mut w = FILE_WATCHER.lock().map_err(|e| format!("Lock error: {}", e))?;
         *w = Some(watcher);
     }
-    
-    // Spawn a thread to collect events
+    {
+        let mut h = WATCH_HANDLE.lock().map_err(|e| format!("Lock error: {}", e))?;
+        *h = Some(handle);
+    }
+
+    // Spawn a thread to collect events: mirror each into the in-memory
+    // "last 100 live" buffer for quick polling, and persist it to this
+    // index's on-disk ring buffer so the history survives a restart.
+    let events_index_dir = index_dir.clone();
     std::thread::spawn(move || {
         while let Ok(event) = rx.recv() {
+            persist_watcher_event(&events_index_dir, &event);
             if let Ok(mut e) = WATCHER_EVENTS.lock() {
                 e.push(event);
+                metrics::counter!("md_scanner_watcher_events_total").increment(1);
                 // Keep only last 100 events
                 if e.len() > 100 {
                     e.remove(0);
+                    metrics::counter!("md_scanner_watcher_events_dropped_total").increment(1);
                 }
             }
         }
     });
-    
+
     Ok(serde_json::json!({
         "success": true,
         "watching": config.paths,
@@ -1766,37 +2950,72 @@ pub async fn start_file_watcher(watch_paths: Option<Vec<String>>) -> Result<serd
 #[tauri::command(rename_all = "camelCase")]
 pub async fn stop_file_watcher() -> Result<serde_json::Value, String> {
     println!("[FILE_WATCHER] stop_file_watcher");
-    
+
     let mut watcher_lock = FILE_WATCHER.lock().map_err(|e| format!("Lock error: {}", e))?;
-    
+
     if let Some(ref mut watcher) = *watcher_lock {
         watcher.stop()?;
     }
-    
+
     *watcher_lock = None;
-    
+    if let Ok(mut h) = WATCH_HANDLE.lock() {
+        *h = None;
+    }
+
     Ok(serde_json::json!({
         "success": true,
         "message": "File watcher stopped"
     }))
 }
 
-/// Get pending file events
+/// Update the running watcher's glob include/exclude filters without
+/// restarting it, which would otherwise drop in-flight debounce and
+/// rename-pairing state.
 #[tauri::command(rename_all = "camelCase")]
-pub async fn get_file_events(clear: Option<bool>) -> Result<serde_json::Value, String> {
-    let mut events = WATCHER_EVENTS.lock()
-        .map_err(|e| format!("Lock error: {}", e))?;
-    
-    let result = events.clone();
-    
-    if clear.unwrap_or(false) {
-        events.clear();
+pub async fn configure_watcher_filters(
+    include_patterns: Vec<String>,
+    exclude_patterns: Vec<String>,
+) -> Result<serde_json::Value, String> {
+    let handle_lock = WATCH_HANDLE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let handle = handle_lock.as_ref().ok_or_else(|| "File watcher is not running".to_string())?;
+    handle.set_filters(&include_patterns, &exclude_patterns)?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "include_patterns": include_patterns,
+        "exclude_patterns": exclude_patterns
+    }))
+}
+
+/// Page through `index_dir`'s persisted watcher event history, most recent
+/// first, rather than only the last 100 live in-memory events. `clear`
+/// additionally empties the in-memory buffer `get_watcher_status` reports
+/// `pending_events` from.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_file_events(
+    index_dir: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    clear: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let log = load_watcher_event_log(&index_dir);
+    let total_count = log.entries.len();
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(100);
+
+    let page: Vec<FileEvent> = log.entries.iter().rev().skip(offset).take(limit).cloned().collect();
+
+    if clear.unwrap_or(false)
+        && let Ok(mut e) = WATCHER_EVENTS.lock()
+    {
+        e.clear();
     }
-    
+
     Ok(serde_json::json!({
         "success": true,
-        "event_count": result.len(),
-        "events": result
+        "total_count": total_count,
+        "event_count": page.len(),
+        "events": page
     }))
 }
 
@@ -1805,7 +3024,7 @@ pub async fn get_file_events(clear: Option<bool>) -> Result<serde_json::Value, S
 pub async fn get_watcher_status() -> Result<serde_json::Value, String> {
     let watcher_lock = FILE_WATCHER.lock().map_err(|e| format!("Lock error: {}", e))?;
     let events = WATCHER_EVENTS.lock().map_err(|e| format!("Lock error: {}", e))?;
-    
+
     let (is_running, paths) = match &*watcher_lock {
         Some(w) => {
             let state = w.get_state()?;
@@ -1813,7 +3032,7 @@ pub async fn get_watcher_status() -> Result<serde_json::Value, String> {
         }
         None => (false, Vec::new()),
     };
-    
+
     Ok(serde_json::json!({
         "success": true,
         "is_running": is_running,
@@ -1822,4 +3041,98 @@ pub async fn get_watcher_status() -> Result<serde_json::Value, String> {
     }))
 }
 
+// ============================================================================
+// TASK STORE COMMANDS
+// ============================================================================
+
+/// Enqueue a task of the given `kind` (e.g. `"generate_embeddings"`) with
+/// arbitrary caller-supplied `details`, and return the new `Task` record.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn enqueue_task(index_dir: String, kind: String, details: Option<serde_json::Value>) -> Result<serde_json::Value, String> {
+    let task = task_store::enqueue_task(&index_dir, &kind, details.unwrap_or(serde_json::Value::Null))?;
+    serde_json::to_value(task).map_err(|e| format!("Failed to serialize task: {}", e))
+}
+
+/// List every task ever enqueued for this index, most recent last.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn list_tasks(index_dir: String) -> Result<serde_json::Value, String> {
+    let tasks = task_store::list_tasks(&index_dir);
+    serde_json::to_value(tasks).map_err(|e| format!("Failed to serialize tasks: {}", e))
+}
+
+/// Look up a single task by id.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_task(index_dir: String, id: String) -> Result<serde_json::Value, String> {
+    match task_store::get_task(&index_dir, &id) {
+        Some(task) => serde_json::to_value(task).map_err(|e| format!("Failed to serialize task: {}", e)),
+        None => Err(format!("Task not found: {}", id)),
+    }
+}
+
+/// Request cancellation of an enqueued or running task. Takes effect on the
+/// next cancellation check inside the task's loop (for `generate_embeddings`,
+/// once per file) rather than immediately, except for a task currently
+/// blocked inside `call_python_for_task`, whose worker process is killed
+/// right away rather than waiting for it to notice the flag.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn cancel_task(index_dir: String, id: String) -> Result<serde_json::Value, String> {
+    let task = task_store::cancel_task(&index_dir, &id)?;
+    crate::handlers::shared().cancel_for_task(&id).await?;
+    serde_json::to_value(task).map_err(|e| format!("Failed to serialize task: {}", e))
+}
+
+// ============================================================================
+// OBSERVABILITY COMMANDS
+// ============================================================================
+
+/// Render every metric recorded so far (scan counts, embedding/validation
+/// request latencies, duplicate deletions, watcher event throughput) as a
+/// Prometheus text exposition string.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn get_metrics() -> Result<String, String> {
+    Ok(observability::render())
+}
+
+/// Start a local HTTP server on `127.0.0.1:<port>` serving the same
+/// exposition text `get_metrics` returns, so an external Prometheus or
+/// Grafana instance can scrape it directly instead of the frontend polling
+/// `get_metrics` on the user's behalf. A no-op if already started.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn start_metrics_server(port: u16) -> Result<(), String> {
+    observability::start_scrape_server(port)
+}
+
+// ============================================================================
+// LOGGING COMMANDS
+// ============================================================================
+
+/// Adjust the process-wide `log` level filter at runtime, e.g. `"debug"`
+/// to see verbose output from the embedding pipeline or file watcher
+/// without restarting the app. Accepts `log`'s own level names
+/// (`error`/`warn`/`info`/`debug`/`trace`/`off`), case-insensitive.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn set_log_level(level: String) -> Result<(), String> {
+    crate::logger::set_level(&level)
+}
+
+// ============================================================================
+// DUMP / RESTORE COMMANDS
+// ============================================================================
+
+/// Bundle everything under `index_dir` into a single portable `.dump` file
+/// (metadata header plus `index.json`, `embeddings.json`, `azure_config.json`,
+/// etc.) for backup or migration to another machine. Unless `include_secrets`
+/// is true, the Azure `api_key` is redacted from the copy written to the
+/// dump so it can be shared safely.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn create_dump(index_dir: String, include_secrets: Option<bool>) -> Result<String, String> {
+    dump::create_dump(&index_dir, include_secrets.unwrap_or(false))
+}
+
+/// Restore a `.dump` file produced by `create_dump` into `target_dir`,
+/// refusing dumps whose format version this build doesn't understand.
+#[tauri::command(rename_all = "camelCase")]
+pub async fn load_dump(dump_path: String, target_dir: String) -> Result<serde_json::Value, String> {
+    dump::load_dump(&dump_path, &target_dir)
+}
 
@@ -0,0 +1,286 @@
+// Portable index dump/restore, modeled on MeiliSearch's dump design: a
+// gzipped tar containing a `metadata.json` (dump format version, crate
+// version, created_at, document count) plus a copy of each known index
+// file. Restoring validates the format version up front and refuses an
+// incompatible dump with a clear message instead of guessing how to adapt it.
+
+use chrono::Local;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// Bumped whenever the dump layout changes incompatibly; `load_dump` refuses
+/// anything with a different version rather than guessing how to adapt it.
+const DUMP_FORMAT_VERSION: u32 = 1;
+
+/// Every file a dump can contain, in the order they're written. Restoring
+/// only ever extracts entries whose name is in this list, so a crafted
+/// archive can't use a `../` path to write outside `target_dir`.
+const INDEX_FILES: &[&str] = &[
+    "index.json",
+    "embeddings.json",
+    "app_state.db",
+    "clusters.json",
+    "azure_config.json",
+    "embedding_progress.json",
+    "embedding_template.json",
+    "storage_config.json",
+    "tasks.json",
+    "error_log.json",
+    "vector_index.bin",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct DumpMetadata {
+    dump_format_version: u32,
+    crate_version: String,
+    created_at: String,
+    document_count: usize,
+    // `true` unless `create_dump` was called with `include_secrets: true`.
+    secrets_redacted: bool,
+}
+
+/// Write `index_dir` as a single gzipped-tar `.dump` file next to it,
+/// returning the dump file's path. Unless `include_secrets` is true,
+/// `azure_config.json`'s `api_key` is blanked out in the copy written to
+/// the archive; the config on disk is never touched.
+pub fn create_dump(index_dir: &str, include_secrets: bool) -> Result<String, String> {
+    let index_path = Path::new(index_dir);
+    if !index_path.exists() {
+        return Err(format!("Index directory does not exist: {}", index_dir));
+    }
+
+    let metadata = DumpMetadata {
+        dump_format_version: DUMP_FORMAT_VERSION,
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        document_count: document_count(index_path),
+        secrets_redacted: !include_secrets,
+    };
+
+    let dump_name = format!(
+        "{}_{}.dump",
+        index_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "index".to_string()),
+        Local::now().format("%Y%m%d%H%M%S")
+    );
+    let dump_path = index_path.parent().unwrap_or_else(|| Path::new(".")).join(dump_name);
+
+    let file = File::create(&dump_path).map_err(|e| format!("Failed to create dump file: {}", e))?;
+    let encoder = GzEncoder::new(file, Compression::default());
+    let mut tar = tar::Builder::new(encoder);
+
+    let metadata_json = serde_json::to_vec_pretty(&metadata).map_err(|e| format!("Failed to serialize dump metadata: {}", e))?;
+    append_bytes(&mut tar, "metadata.json", &metadata_json)?;
+
+    for name in INDEX_FILES {
+        let path = index_path.join(name);
+        if !path.exists() {
+            continue;
+        }
+
+        if *name == "azure_config.json" && !include_secrets {
+            append_bytes(&mut tar, name, &redact_azure_config(&path)?)?;
+        } else {
+            let mut f = File::open(&path).map_err(|e| format!("Failed to open {}: {}", name, e))?;
+            tar.append_file(name, &mut f).map_err(|e| format!("Failed to add {} to dump: {}", name, e))?;
+        }
+    }
+
+    tar.finish().map_err(|e| format!("Failed to finalize dump: {}", e))?;
+
+    Ok(dump_path.to_string_lossy().to_string())
+}
+
+fn append_bytes<W: std::io::Write>(tar: &mut tar::Builder<W>, name: &str, bytes: &[u8]) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, name, bytes).map_err(|e| format!("Failed to add {} to dump: {}", name, e))
+}
+
+/// `azure_config.json` with `api_key` blanked out, for a dump created
+/// without `include_secrets`.
+fn redact_azure_config(path: &Path) -> Result<Vec<u8>, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read azure_config.json: {}", e))?;
+    let mut value: serde_json::Value = serde_json::from_str(&content).map_err(|e| format!("Failed to parse azure_config.json: {}", e))?;
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("api_key".to_string(), serde_json::Value::String(String::new()));
+    }
+    serde_json::to_vec_pretty(&value).map_err(|e| format!("Failed to re-serialize azure_config.json: {}", e))
+}
+
+fn document_count(index_path: &Path) -> usize {
+    fs::read_to_string(index_path.join("index.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .and_then(|v| v.get("files").and_then(|f| f.as_array()).map(|a| a.len()))
+        .unwrap_or(0)
+}
+
+/// Extract a dump created by `create_dump` into `target_dir` (created if it
+/// doesn't exist). Refuses with a clear error if the dump has no
+/// `metadata.json` at all (not a dump this code produced) or if its format
+/// version doesn't match what this build understands.
+pub fn load_dump(dump_path: &str, target_dir: &str) -> Result<serde_json::Value, String> {
+    let file = File::open(dump_path).map_err(|e| format!("Failed to open dump file: {}", e))?;
+    let decoder = GzDecoder::new(file);
+    let mut archive = tar::Archive::new(decoder);
+
+    fs::create_dir_all(target_dir).map_err(|e| format!("Failed to create target directory: {}", e))?;
+
+    let mut metadata: Option<DumpMetadata> = None;
+    let mut restored_files = Vec::new();
+
+    for entry in archive.entries().map_err(|e| format!("Failed to read dump archive: {}", e))? {
+        let mut entry = entry.map_err(|e| format!("Failed to read dump entry: {}", e))?;
+        let name = entry.path().map_err(|e| format!("Invalid entry path in dump: {}", e))?.to_string_lossy().to_string();
+
+        if name == "metadata.json" {
+            let mut content = String::new();
+            entry.read_to_string(&mut content).map_err(|e| format!("Failed to read dump metadata: {}", e))?;
+            let parsed: DumpMetadata = serde_json::from_str(&content).map_err(|e| format!("Failed to parse dump metadata: {}", e))?;
+            if parsed.dump_format_version != DUMP_FORMAT_VERSION {
+                return Err(format!(
+                    "Dump format version {} is not compatible with this build (expects version {})",
+                    parsed.dump_format_version, DUMP_FORMAT_VERSION
+                ));
+            }
+            metadata = Some(parsed);
+            continue;
+        }
+
+        if !INDEX_FILES.contains(&name.as_str()) {
+            continue;
+        }
+
+        let dest = Path::new(target_dir).join(&name);
+        entry.unpack(&dest).map_err(|e| format!("Failed to restore {}: {}", name, e))?;
+        restored_files.push(name);
+    }
+
+    let metadata = metadata.ok_or_else(|| "Dump is missing metadata.json; this doesn't look like an md-scanner dump".to_string())?;
+
+    if metadata.dump_format_version != DUMP_FORMAT_VERSION {
+        return Err(format!(
+            "Dump format version {} is not compatible with this build (expects version {})",
+            metadata.dump_format_version, DUMP_FORMAT_VERSION
+        ));
+    }
+
+    Ok(serde_json::json!({
+        "restored_files": restored_files,
+        "document_count": metadata.document_count,
+        "created_at": metadata.created_at,
+        "crate_version": metadata.crate_version,
+        "secrets_redacted": metadata.secrets_redacted,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn test_dir(prefix: &str) -> String {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!("md_dump_test_{}_{}_{}", prefix, std::process::id(), id));
+        fs::create_dir_all(&dir).unwrap();
+        dir.to_string_lossy().to_string()
+    }
+
+    fn write_sample_index(index_dir: &str) {
+        fs::write(
+            Path::new(index_dir).join("index.json"),
+            serde_json::json!({ "files": [{"path": "a.md"}, {"path": "b.md"}] }).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            Path::new(index_dir).join("azure_config.json"),
+            serde_json::json!({ "endpoint": "https://example", "api_key": "super-secret", "deployment_name": "dep", "api_version": "2024-02-01" }).to_string(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_round_trip_restores_files_and_document_count() {
+        let index_dir = test_dir("src");
+        write_sample_index(&index_dir);
+
+        let dump_path = create_dump(&index_dir, false).unwrap();
+        let target_dir = test_dir("dst");
+        let result = load_dump(&dump_path, &target_dir).unwrap();
+
+        assert_eq!(result["document_count"], 2);
+        assert!(fs::read_to_string(Path::new(&target_dir).join("index.json")).unwrap().contains("a.md"));
+    }
+
+    #[test]
+    fn test_redacts_api_key_by_default() {
+        let index_dir = test_dir("src");
+        write_sample_index(&index_dir);
+
+        let dump_path = create_dump(&index_dir, false).unwrap();
+        let target_dir = test_dir("dst");
+        load_dump(&dump_path, &target_dir).unwrap();
+
+        let restored = fs::read_to_string(Path::new(&target_dir).join("azure_config.json")).unwrap();
+        assert!(!restored.contains("super-secret"));
+    }
+
+    #[test]
+    fn test_include_secrets_preserves_api_key() {
+        let index_dir = test_dir("src");
+        write_sample_index(&index_dir);
+
+        let dump_path = create_dump(&index_dir, true).unwrap();
+        let target_dir = test_dir("dst");
+        load_dump(&dump_path, &target_dir).unwrap();
+
+        let restored = fs::read_to_string(Path::new(&target_dir).join("azure_config.json")).unwrap();
+        assert!(restored.contains("super-secret"));
+    }
+
+    #[test]
+    fn test_rejects_incompatible_format_version() {
+        let index_dir = test_dir("src");
+        write_sample_index(&index_dir);
+
+        let dump_path = create_dump(&index_dir, false).unwrap();
+
+        // Tamper with the dump's metadata.json to simulate a future/older
+        // format version this build doesn't understand.
+        let file = File::open(&dump_path).unwrap();
+        let decoder = GzDecoder::new(file);
+        let mut archive = tar::Archive::new(decoder);
+        let patched_path = format!("{}.patched", dump_path);
+        let out = File::create(&patched_path).unwrap();
+        let mut tar_out = tar::Builder::new(GzEncoder::new(out, Compression::default()));
+
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_string_lossy().to_string();
+            let mut bytes = Vec::new();
+            entry.read_to_end(&mut bytes).unwrap();
+            if name == "metadata.json" {
+                let mut value: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+                value["dump_format_version"] = serde_json::json!(DUMP_FORMAT_VERSION + 1);
+                bytes = serde_json::to_vec(&value).unwrap();
+            }
+            append_bytes(&mut tar_out, &name, &bytes).unwrap();
+        }
+        tar_out.finish().unwrap();
+        // Drop (and with it the inner `GzEncoder`) before reading the file
+        // back, so the gzip footer is actually flushed to disk.
+        drop(tar_out);
+
+        let target_dir = test_dir("dst");
+        let err = load_dump(&patched_path, &target_dir).unwrap_err();
+        assert!(err.contains("not compatible"), "unexpected error: {}", err);
+    }
+}
@@ -0,0 +1,529 @@
+// Pluggable embedding backends. `generate_embeddings` used to talk to Azure
+// OpenAI directly; now it dispatches through this trait so a user with no
+// cloud account (or privacy constraints that rule one out) can point the
+// app at vanilla OpenAI or a local Ollama server instead.
+
+use async_trait::async_trait;
+
+use crate::commands::AzureConfig;
+use crate::error::AppError;
+
+/// A backend capable of turning text into embedding vectors. Implementors
+/// own whatever retry/rate-limit handling their API needs; callers just see
+/// a batch of inputs go in and the same number of vectors come out, in order.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Embed a batch of inputs, one vector per input, in the same order.
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, String>;
+
+    /// Confirm this provider is reachable and configured correctly, e.g. by
+    /// issuing a small test embedding request. Returns a JSON summary
+    /// (`success`, a diagnostic `message`, etc.) for an expected failure
+    /// (bad key, wrong endpoint) rather than an `Err`, so the UI can show it
+    /// inline; `Err` is reserved for validation that couldn't even run.
+    async fn validate(&self) -> Result<serde_json::Value, String>;
+
+    /// The vector length this provider's model produces, so a cache built
+    /// with a different provider/model can be flagged before it's trusted.
+    fn dimensions(&self) -> usize;
+
+    /// A short identifier stored alongside cached embeddings, e.g.
+    /// `"azure:text-embedding-ada-002"`.
+    fn name(&self) -> String;
+}
+
+/// Build the `EmbeddingProvider` named by `config.provider` ("azure",
+/// "openai", "ollama", or "local"), using whichever of `config`'s fields
+/// that provider needs.
+pub fn build_provider(config: &AzureConfig) -> Result<Box<dyn EmbeddingProvider>, String> {
+    match config.provider.as_str() {
+        "azure" => {
+            if config.endpoint.is_empty() || config.api_key.is_empty() || config.deployment_name.is_empty() {
+                return Err(AppError::ConfigIncomplete {
+                    what: "Azure config".to_string(),
+                    reason: "endpoint, API key, and deployment name must all be set".to_string(),
+                }
+                .into());
+            }
+            Ok(Box::new(AzureOpenAiProvider::new(
+                config.endpoint.clone(),
+                config.api_key.clone(),
+                config.deployment_name.clone(),
+                if config.api_version.is_empty() { "2024-02-01".to_string() } else { config.api_version.clone() },
+            )?))
+        }
+        "openai" => {
+            if config.api_key.is_empty() || config.deployment_name.is_empty() {
+                return Err(AppError::ConfigIncomplete {
+                    what: "OpenAI config".to_string(),
+                    reason: "API key and model name must both be set".to_string(),
+                }
+                .into());
+            }
+            Ok(Box::new(OpenAiProvider::new(config.api_key.clone(), config.deployment_name.clone())?))
+        }
+        "ollama" => {
+            let base_url = if config.endpoint.is_empty() { "http://localhost:11434".to_string() } else { config.endpoint.clone() };
+            let model = if config.deployment_name.is_empty() { "nomic-embed-text".to_string() } else { config.deployment_name.clone() };
+            Ok(Box::new(OllamaProvider::new(base_url, model)?))
+        }
+        "local" => Ok(Box::new(LocalProvider::new())),
+        other => Err(format!("Unknown embedding provider: {}", other)),
+    }
+}
+
+/// Talks to an Azure OpenAI embeddings deployment, including the
+/// rate-limit backoff and unsupported-api-version fallback the direct
+/// Azure integration always needed.
+pub struct AzureOpenAiProvider {
+    endpoint: String,
+    api_key: String,
+    deployment_name: String,
+    api_version: std::sync::Mutex<String>,
+    client: reqwest::Client,
+}
+
+impl AzureOpenAiProvider {
+    pub fn new(endpoint: String, api_key: String, deployment_name: String, api_version: String) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        let mut base = endpoint.trim_end_matches('/').to_string();
+        if !base.ends_with("/openai") && !base.ends_with("/openai/") {
+            base = format!("{}/openai", base);
+        }
+
+        Ok(Self {
+            endpoint: base,
+            api_key,
+            deployment_name,
+            api_version: std::sync::Mutex::new(api_version),
+            client,
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for AzureOpenAiProvider {
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let mut results = Vec::with_capacity(inputs.len());
+
+        for input in inputs {
+            let mut retries = 0;
+            let max_retries = 3;
+            let mut embedding: Option<Vec<f32>> = None;
+
+            while retries < max_retries && embedding.is_none() {
+                let api_version = self.api_version.lock().map_err(|e| format!("Lock error: {}", e))?.clone();
+                let url = format!("{}/deployments/{}/embeddings?api-version={}", self.endpoint, self.deployment_name, api_version);
+                let request_body = serde_json::json!({ "input": input });
+
+                let request_started = std::time::Instant::now();
+                let response = self.client
+                    .post(&url)
+                    .header("api-key", &self.api_key)
+                    .header("Content-Type", "application/json")
+                    .json(&request_body)
+                    .send()
+                    .await;
+                let record_duration = |status_code: &str| {
+                    metrics::histogram!(
+                        "md_scanner_embedding_request_duration_seconds",
+                        "status_code" => status_code.to_string(),
+                        "api_version" => api_version.clone(),
+                    )
+                    .record(request_started.elapsed().as_secs_f64());
+                };
+
+                match response {
+                    Ok(response) => {
+                        record_duration(&response.status().as_u16().to_string());
+                        if response.status().is_success() {
+                            let json: serde_json::Value = response.json().await
+                                .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+                            if json.get("error").is_some() {
+                                return Err(format!("API error: {}", json["error"]));
+                            }
+
+                            let vector = json["data"][0]["embedding"].as_array()
+                                .ok_or_else(|| format!("Unexpected response shape: {}", json))?
+                                .iter()
+                                .filter_map(|v| v.as_f64().map(|f| f as f32))
+                                .collect();
+                            embedding = Some(vector);
+                        } else if response.status().as_u16() == 429 {
+                            let wait_time = 2u64.pow(retries as u32) * 1000;
+                            tokio::time::sleep(tokio::time::Duration::from_millis(wait_time)).await;
+                            retries += 1;
+                        } else {
+                            let status = response.status();
+                            let error_text = response.text().await.unwrap_or_default();
+
+                            if error_text.contains("API version not supported") {
+                                let mut api_version = self.api_version.lock().map_err(|e| format!("Lock error: {}", e))?;
+                                if *api_version != "2023-10-01" {
+                                    *api_version = "2023-10-01".to_string();
+                                    retries = 0;
+                                    continue;
+                                }
+                            }
+
+                            return Err(format!("{} - {}", status, error_text));
+                        }
+                    }
+                    Err(e) => {
+                        record_duration("error");
+                        if retries < max_retries - 1 {
+                            let wait_time = 2u64.pow(retries as u32) * 500;
+                            tokio::time::sleep(tokio::time::Duration::from_millis(wait_time)).await;
+                            retries += 1;
+                        } else {
+                            return Err(e.to_string());
+                        }
+                    }
+                }
+            }
+
+            results.push(embedding.ok_or_else(|| "Exhausted retries without a response".to_string())?);
+        }
+
+        Ok(results)
+    }
+
+    /// Probe the deployment with a one-word embedding request, trying the
+    /// configured API version and then a set of known-good fallbacks (Azure
+    /// regularly retires old ones), and suggesting a corrected endpoint for
+    /// the common mistake of pointing at an AI Studio project URL instead of
+    /// the Cognitive Services resource itself.
+    async fn validate(&self) -> Result<serde_json::Value, String> {
+        let mut suggested: Option<String> = None;
+        if self.endpoint.contains("/api/projects") || self.endpoint.contains("/api/") {
+            if let Ok(url) = reqwest::Url::parse(&self.endpoint) {
+                if let Some(host) = url.host_str() {
+                    if host.contains("services.ai.azure.com") {
+                        if let Some(prefix) = host.split('.').next() {
+                            suggested = Some(format!("https://{}.cognitiveservices.azure.com", prefix));
+                        }
+                    } else {
+                        suggested = Some(format!("https://{}", host));
+                    }
+                }
+            }
+        } else if self.endpoint.contains("services.ai.azure.com") {
+            if let Ok(url) = reqwest::Url::parse(&self.endpoint) {
+                if let Some(host) = url.host_str() {
+                    if let Some(prefix) = host.split('.').next() {
+                        suggested = Some(format!("https://{}.cognitiveservices.azure.com", prefix));
+                    }
+                }
+            }
+        }
+
+        let initial_version = self.api_version.lock().map_err(|e| format!("Lock error: {}", e))?.clone();
+        let fallback_versions = vec!["2024-02-01".to_string(), "2023-10-01".to_string(), "2023-05-15".to_string()];
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(8))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        let mut tried_versions: Vec<String> = Vec::new();
+        for v in std::iter::once(initial_version).chain(fallback_versions) {
+            if tried_versions.contains(&v) {
+                continue;
+            }
+            tried_versions.push(v.clone());
+
+            let url = format!("{}/deployments/{}/embeddings?api-version={}", self.endpoint, self.deployment_name, v);
+            let body = serde_json::json!({ "input": ["healthcheck"] });
+
+            let request_started = std::time::Instant::now();
+            let response = client.post(&url).header("api-key", &self.api_key).json(&body).send().await;
+            let record_duration = |status_code: &str| {
+                metrics::histogram!(
+                    "md_scanner_azure_validate_duration_seconds",
+                    "status_code" => status_code.to_string(),
+                    "api_version" => v.clone(),
+                )
+                .record(request_started.elapsed().as_secs_f64());
+            };
+
+            match response {
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    record_duration(&status.to_string());
+                    if response.status().is_success() {
+                        return Ok(serde_json::json!({
+                            "success": true,
+                            "message": "Validation succeeded",
+                            "tried_versions": tried_versions,
+                            "final_url": url,
+                            "status_code": status
+                        }));
+                    }
+
+                    let text = response.text().await.unwrap_or_default();
+                    if text.contains("API version not supported") {
+                        continue;
+                    }
+                    return Ok(serde_json::json!({
+                        "success": false,
+                        "message": format!("Server returned {}: {}", status, text),
+                        "tried_versions": tried_versions,
+                        "final_url": url,
+                        "status_code": status,
+                        "suggested_endpoint": suggested
+                    }));
+                }
+                Err(e) => {
+                    record_duration("error");
+                    return Ok(serde_json::json!({
+                        "success": false,
+                        "message": format!("Request failed: {}", e),
+                        "tried_versions": tried_versions,
+                        "suggested_endpoint": suggested
+                    }));
+                }
+            }
+        }
+
+        Ok(serde_json::json!({
+            "success": false,
+            "message": "All tried API versions failed",
+            "tried_versions": tried_versions,
+            "suggested_endpoint": suggested
+        }))
+    }
+
+    fn dimensions(&self) -> usize {
+        1536 // text-embedding-ada-002 / text-embedding-3-small
+    }
+
+    fn name(&self) -> String {
+        format!("azure:{}", self.deployment_name)
+    }
+}
+
+/// Talks to the vanilla OpenAI embeddings API (`Authorization: Bearer`
+/// instead of Azure's `api-key` header, no deployment/api-version concept).
+pub struct OpenAiProvider {
+    api_key: String,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl OpenAiProvider {
+    pub fn new(api_key: String, model: String) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        Ok(Self { api_key, model, client })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiProvider {
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let request_body = serde_json::json!({
+            "model": self.model,
+            "input": inputs,
+        });
+
+        let response = self.client
+            .post("https://api.openai.com/v1/embeddings")
+            .bearer_auth(&self.api_key)
+            .header("Content-Type", "application/json")
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| format!("Request to OpenAI failed: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(format!("{} - {}", status, error_text));
+        }
+
+        let json: serde_json::Value = response.json().await
+            .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+        if json.get("error").is_some() {
+            return Err(format!("API error: {}", json["error"]));
+        }
+
+        let data = json["data"].as_array()
+            .ok_or_else(|| format!("Unexpected response shape: {}", json))?;
+
+        data.iter()
+            .map(|entry| {
+                entry["embedding"].as_array()
+                    .map(|arr| arr.iter().filter_map(|v| v.as_f64().map(|f| f as f32)).collect())
+                    .ok_or_else(|| format!("Unexpected response shape: {}", json))
+            })
+            .collect()
+    }
+
+    /// OpenAI has no api-version or endpoint-shape pitfalls to diagnose, so
+    /// validation is just a one-word embedding request.
+    async fn validate(&self) -> Result<serde_json::Value, String> {
+        match self.embed(&["healthcheck".to_string()]).await {
+            Ok(_) => Ok(serde_json::json!({ "success": true, "message": "Validation succeeded" })),
+            Err(e) => Ok(serde_json::json!({ "success": false, "message": e })),
+        }
+    }
+
+    fn dimensions(&self) -> usize {
+        1536 // text-embedding-3-small / text-embedding-ada-002
+    }
+
+    fn name(&self) -> String {
+        format!("openai:{}", self.model)
+    }
+}
+
+/// Talks to a local Ollama server's embeddings endpoint. Ollama embeds one
+/// prompt per request, so a batch is just that many sequential calls.
+pub struct OllamaProvider {
+    base_url: String,
+    model: String,
+    client: reqwest::Client,
+}
+
+impl OllamaProvider {
+    pub fn new(base_url: String, model: String) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(120))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        Ok(Self { base_url: base_url.trim_end_matches('/').to_string(), model, client })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaProvider {
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        let mut results = Vec::with_capacity(inputs.len());
+
+        for input in inputs {
+            let request_body = serde_json::json!({
+                "model": self.model,
+                "prompt": input,
+            });
+
+            let response = self.client
+                .post(format!("{}/api/embeddings", self.base_url))
+                .json(&request_body)
+                .send()
+                .await
+                .map_err(|e| format!("Request to Ollama failed: {} (is `ollama serve` running?)", e))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("{} - {}", status, error_text));
+            }
+
+            let json: serde_json::Value = response.json().await
+                .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+
+            let vector = json["embedding"].as_array()
+                .ok_or_else(|| format!("Unexpected response shape: {}", json))?
+                .iter()
+                .filter_map(|v| v.as_f64().map(|f| f as f32))
+                .collect();
+            results.push(vector);
+        }
+
+        Ok(results)
+    }
+
+    /// Like OpenAI, a one-word embedding request is enough to confirm the
+    /// server is up and the model is pulled; the error message already says
+    /// to check `ollama serve` when the connection itself fails.
+    async fn validate(&self) -> Result<serde_json::Value, String> {
+        match self.embed(&["healthcheck".to_string()]).await {
+            Ok(_) => Ok(serde_json::json!({ "success": true, "message": "Validation succeeded" })),
+            Err(e) => Ok(serde_json::json!({ "success": false, "message": e })),
+        }
+    }
+
+    fn dimensions(&self) -> usize {
+        768 // nomic-embed-text; mxbai-embed-large and others differ
+    }
+
+    fn name(&self) -> String {
+        format!("ollama:{}", self.model)
+    }
+}
+
+/// A dependency-free, fully offline embedder for users without a cloud key
+/// or a local inference server. It hashes lowercased words into a
+/// fixed-size bag-of-words vector (the "hashing trick"), which is enough to
+/// cluster and rank documents by shared vocabulary. It is not a real
+/// sentence-transformer: bundling one would mean shipping hundreds of MB of
+/// model weights or fetching them over the network, either of which
+/// defeats the point of an offline backend.
+const LOCAL_PROVIDER_DIMENSIONS: usize = 256;
+
+pub struct LocalProvider;
+
+impl LocalProvider {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for LocalProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for LocalProvider {
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        Ok(inputs.iter().map(|text| hash_embed(text, LOCAL_PROVIDER_DIMENSIONS)).collect())
+    }
+
+    async fn validate(&self) -> Result<serde_json::Value, String> {
+        Ok(serde_json::json!({
+            "success": true,
+            "message": "Local provider requires no network access or credentials"
+        }))
+    }
+
+    fn dimensions(&self) -> usize {
+        LOCAL_PROVIDER_DIMENSIONS
+    }
+
+    fn name(&self) -> String {
+        "local:hashing-256".to_string()
+    }
+}
+
+/// Feature-hash `text`'s lowercased words into an L2-normalized vector of
+/// length `dims`, using blake3 (already used elsewhere in this crate for
+/// content hashing) so unrelated documents land far apart in the vector
+/// space and documents sharing vocabulary land close together.
+fn hash_embed(text: &str, dims: usize) -> Vec<f32> {
+    let mut vector = vec![0f32; dims];
+    for word in text.split_whitespace() {
+        let digest = blake3::hash(word.to_lowercase().as_bytes());
+        let bytes = digest.as_bytes();
+        let bucket = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize % dims;
+        let sign = if bytes[4] & 1 == 0 { 1.0 } else { -1.0 };
+        vector[bucket] += sign;
+    }
+
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in vector.iter_mut() {
+            *v /= norm;
+        }
+    }
+    vector
+}
@@ -0,0 +1,159 @@
+// A per-index template controlling what text actually gets embedded,
+// instead of always embedding a file's whole raw content. Stored as
+// `embedding_template.json` next to `azure_config.json`, the same way that
+// file's sibling `storage_config.json`/`azure_config.json` configs live
+// alongside the index they apply to.
+//
+// The template language is deliberately tiny (a handful of `{{ file.* }}`
+// fields and a `head` filter) rather than a full Liquid implementation,
+// since that's all `generate_embeddings` needs: pick which parts of a file
+// go into the embedded text and how much of its content to include.
+
+use crate::commands::FileEntry;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmbeddingTemplate {
+    pub template: String,
+}
+
+impl Default for EmbeddingTemplate {
+    fn default() -> Self {
+        // Matches the behavior before templates existed: embed the whole
+        // (chunked) file content verbatim.
+        Self { template: "{{ file.content }}".to_string() }
+    }
+}
+
+fn template_path(index_dir: &str) -> PathBuf {
+    Path::new(index_dir).join("embedding_template.json")
+}
+
+/// The template configured for this index, or the default (whole-content)
+/// template if none has been saved yet.
+pub fn get_template(index_dir: &str) -> EmbeddingTemplate {
+    fs::read_to_string(template_path(index_dir))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_template(index_dir: &str, template: &EmbeddingTemplate) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(template)
+        .map_err(|e| format!("Failed to serialize embedding template: {}", e))?;
+    fs::write(template_path(index_dir), json).map_err(|e| format!("Failed to write embedding template: {}", e))
+}
+
+/// The result of rendering a template: the text to embed, plus any
+/// `{{ ... }}` placeholders that didn't match a known field, so
+/// `validate_embedding_template` can surface a typo before a long run.
+#[derive(Debug, Clone)]
+pub struct RenderReport {
+    pub rendered: String,
+    pub unknown_fields: Vec<String>,
+}
+
+/// Render `template` against one file. Supports `{{ file.name }}`,
+/// `{{ file.path }}`, `{{ file.extension }}`, `{{ file.dir }}` (its parent
+/// directory), and `{{ file.content }}` — optionally piped through
+/// `| head: N` to take just the first `N` characters. An unrecognized
+/// field or filter is dropped from the output and reported in
+/// `unknown_fields` rather than aborting the render.
+pub fn render(template: &str, file: &FileEntry, content: &str) -> RenderReport {
+    let mut unknown_fields = Vec::new();
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find("{{") {
+        rendered.push_str(&rest[..open]);
+        let after_open = &rest[open + 2..];
+        let Some(close) = after_open.find("}}") else {
+            // Unterminated placeholder; keep it verbatim rather than
+            // silently dropping the rest of the template.
+            rendered.push_str(&rest[open..]);
+            rest = "";
+            break;
+        };
+        let expr = after_open[..close].trim();
+        rendered.push_str(&resolve_field(expr, file, content, &mut unknown_fields));
+        rest = &after_open[close + 2..];
+    }
+    rendered.push_str(rest);
+
+    RenderReport { rendered, unknown_fields }
+}
+
+fn resolve_field(expr: &str, file: &FileEntry, content: &str, unknown_fields: &mut Vec<String>) -> String {
+    let mut parts = expr.split('|').map(|p| p.trim());
+    let field = parts.next().unwrap_or("");
+
+    let mut value = match field {
+        "file.name" => file.name.clone(),
+        "file.path" => file.path.clone(),
+        "file.extension" => file.extension.clone(),
+        "file.dir" => Path::new(&file.path)
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        "file.content" => content.to_string(),
+        other => {
+            unknown_fields.push(other.to_string());
+            return String::new();
+        }
+    };
+
+    for filter in parts {
+        match filter.strip_prefix("head:").map(|n| n.trim().parse::<usize>()) {
+            Some(Ok(n)) => value = value.chars().take(n).collect(),
+            _ => unknown_fields.push(format!("{} | {}", field, filter)),
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_file() -> FileEntry {
+        FileEntry {
+            path: "/docs/notes/readme.md".to_string(),
+            name: "readme.md".to_string(),
+            size: 100,
+            modified: "2026-01-01".to_string(),
+            extension: "md".to_string(),
+            record_id: None,
+            parent_file: None,
+        }
+    }
+
+    #[test]
+    fn test_default_template_embeds_raw_content() {
+        let report = render(&EmbeddingTemplate::default().template, &sample_file(), "hello world");
+        assert_eq!(report.rendered, "hello world");
+        assert!(report.unknown_fields.is_empty());
+    }
+
+    #[test]
+    fn test_renders_name_path_and_truncated_content() {
+        let template = "Title: {{ file.name }}\nDir: {{ file.dir }}\n{{ file.content | head: 5 }}";
+        let report = render(template, &sample_file(), "hello world");
+        assert_eq!(report.rendered, "Title: readme.md\nDir: /docs/notes\nhello");
+        assert!(report.unknown_fields.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_field_is_reported_and_dropped() {
+        let report = render("{{ file.bogus }}", &sample_file(), "hello");
+        assert_eq!(report.rendered, "");
+        assert_eq!(report.unknown_fields, vec!["file.bogus".to_string()]);
+    }
+
+    #[test]
+    fn test_unterminated_placeholder_is_kept_verbatim() {
+        let report = render("before {{ file.name", &sample_file(), "hello");
+        assert_eq!(report.rendered, "before {{ file.name");
+    }
+}
@@ -0,0 +1,121 @@
+// A typed error taxonomy for command handlers. Everything still returns
+// `Result<_, String>` at the Tauri command boundary (changing that is a
+// much bigger, IPC-breaking rewrite), but internally an `AppError` carries
+// a stable machine-readable `code()` and HTTP-style `category()` alongside
+// its human-readable message, so `log_error` can finally populate
+// `ErrorLogEntry.error_code` with something a frontend can branch on
+// instead of parsing prose out of `error_message`.
+
+use serde::Serialize;
+use std::fmt;
+
+/// A typed error with a stable code/category pair, in addition to its
+/// human-readable `Display` message. Converts to `String` via `.into()`
+/// so it drops into any existing `Result<_, String>` call site with `?`.
+#[derive(Debug, Clone)]
+pub enum AppError {
+    IndexNotFound { index_dir: String },
+    ConfigMissing { what: String },
+    ConfigIncomplete { what: String, reason: String },
+    ProviderRateLimited { provider: String },
+    ProviderAuth { provider: String },
+    ProviderUnsupportedVersion { provider: String },
+    FileRead { path: String, reason: String },
+    Serialization { what: String, reason: String },
+}
+
+impl AppError {
+    /// Stable, machine-readable identifier for this error variant.
+    /// Safe to match on in the frontend; never changes for a given variant.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::IndexNotFound { .. } => "index_not_found",
+            AppError::ConfigMissing { .. } => "config_missing",
+            AppError::ConfigIncomplete { .. } => "config_incomplete",
+            AppError::ProviderRateLimited { .. } => "provider_rate_limited",
+            AppError::ProviderAuth { .. } => "provider_auth",
+            AppError::ProviderUnsupportedVersion { .. } => "provider_unsupported_version",
+            AppError::FileRead { .. } => "file_read",
+            AppError::Serialization { .. } => "serialization",
+        }
+    }
+
+    /// HTTP-style category, for UIs that want to group errors the way they
+    /// would group response statuses (without actually being one).
+    pub fn category(&self) -> &'static str {
+        match self {
+            AppError::IndexNotFound { .. } => "not_found",
+            AppError::ConfigMissing { .. } | AppError::ConfigIncomplete { .. } => "bad_request",
+            AppError::ProviderRateLimited { .. } => "rate_limited",
+            AppError::ProviderAuth { .. } => "unauthorized",
+            AppError::ProviderUnsupportedVersion { .. } => "bad_gateway",
+            AppError::FileRead { .. } => "io_error",
+            AppError::Serialization { .. } => "internal",
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::IndexNotFound { index_dir } => {
+                write!(f, "Index not found at '{}'. Please scan a directory first.", index_dir)
+            }
+            AppError::ConfigMissing { what } => write!(f, "{} not found.", what),
+            AppError::ConfigIncomplete { what, reason } => write!(f, "{} is incomplete: {}", what, reason),
+            AppError::ProviderRateLimited { provider } => write!(f, "{} rate-limited the request.", provider),
+            AppError::ProviderAuth { provider } => write!(f, "{} rejected the request's credentials.", provider),
+            AppError::ProviderUnsupportedVersion { provider } => {
+                write!(f, "{} does not support the requested API version.", provider)
+            }
+            AppError::FileRead { path, reason } => write!(f, "Failed to read '{}': {}", path, reason),
+            AppError::Serialization { what, reason } => write!(f, "Failed to serialize {}: {}", what, reason),
+        }
+    }
+}
+
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        err.to_string()
+    }
+}
+
+/// `{ code, message, type }` wire shape for an `AppError`, for callers that
+/// want to hand the frontend the structured error instead of just its
+/// stringified message.
+#[derive(Serialize, Debug, Clone)]
+pub struct AppErrorPayload {
+    pub code: String,
+    pub message: String,
+    #[serde(rename = "type")]
+    pub category: String,
+}
+
+impl From<&AppError> for AppErrorPayload {
+    fn from(err: &AppError) -> Self {
+        AppErrorPayload {
+            code: err.code().to_string(),
+            message: err.to_string(),
+            category: err.category().to_string(),
+        }
+    }
+}
+
+/// Classify a provider's error text (as returned by `EmbeddingProvider::embed`)
+/// into the closest `AppError` variant, so `generate_embeddings`'s
+/// `"api_error"` log entries get a real code instead of a guess. Falls back
+/// to treating the error as a generic file-read-adjacent failure isn't
+/// appropriate here, so unrecognized errors keep `None` rather than a
+/// misleading code.
+pub fn classify_provider_error(provider: &str, error_text: &str) -> Option<AppError> {
+    if error_text.contains("429") {
+        return Some(AppError::ProviderRateLimited { provider: provider.to_string() });
+    }
+    if error_text.contains("API version not supported") {
+        return Some(AppError::ProviderUnsupportedVersion { provider: provider.to_string() });
+    }
+    if error_text.contains("401") || error_text.contains("403") {
+        return Some(AppError::ProviderAuth { provider: provider.to_string() });
+    }
+    None
+}
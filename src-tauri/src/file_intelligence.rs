@@ -3,8 +3,11 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
 use chrono::{DateTime, Local};
 
 // ============================================================================
@@ -102,6 +105,188 @@ pub enum SuggestionAction {
     CreateSubfolder { folder_name: String },
     Archive,
     LeaveAlone,
+    /// `keep_path` is the copy we'd keep; `duplicate_of` names it again so
+    /// a consumer rendering just this one suggestion (without the group it
+    /// came from) can still say "this is a duplicate of X".
+    DeleteDuplicate { keep_path: String, duplicate_of: String },
+}
+
+/// A condition a [`SuggestionRule`] tests a document against. Composable via
+/// `All`/`Any` so a rule can require several conditions (e.g. "in Downloads
+/// AND named like a receipt") without a dedicated enum variant per
+/// combination.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RuleMatcher {
+    /// Filename (case-insensitive) contains any of these substrings.
+    FilenameContains(Vec<String>),
+    /// Extension (case-insensitive) is one of these.
+    Extension(Vec<String>),
+    /// Classified as this `DocumentType`.
+    DocType(DocumentType),
+    /// Parent directory (case-insensitive) contains any of these substrings.
+    ParentDirContains(Vec<String>),
+    /// Filename matches this naming `PatternType`.
+    NamingPattern(PatternType),
+    /// Filename looks generic/auto-generated (see `is_poorly_named`).
+    PoorlyNamed,
+    All(Vec<RuleMatcher>),
+    Any(Vec<RuleMatcher>),
+}
+
+impl RuleMatcher {
+    fn matches(&self, doc: &DiscoveredDocument) -> bool {
+        match self {
+            RuleMatcher::FilenameContains(keywords) => {
+                let name_lower = doc.name.to_lowercase();
+                keywords.iter().any(|k| name_lower.contains(&k.to_lowercase()))
+            }
+            RuleMatcher::Extension(exts) => exts.iter().any(|e| e.eq_ignore_ascii_case(&doc.extension)),
+            RuleMatcher::DocType(doc_type) => &doc.doc_type == doc_type,
+            RuleMatcher::ParentDirContains(keywords) => {
+                let parent_lower = doc.parent_dir.to_lowercase();
+                keywords.iter().any(|k| parent_lower.contains(&k.to_lowercase()))
+            }
+            RuleMatcher::NamingPattern(pattern) => &classify_name_pattern(&doc.name) == pattern,
+            RuleMatcher::PoorlyNamed => is_poorly_named(&doc.name),
+            RuleMatcher::All(matchers) => matchers.iter().all(|m| m.matches(doc)),
+            RuleMatcher::Any(matchers) => matchers.iter().any(|m| m.matches(doc)),
+        }
+    }
+}
+
+/// A user- or built-in-defined organization rule. `analyze_document` tests
+/// every enabled rule's `matcher` against a document and takes the
+/// highest-confidence match, so users can add, disable, or override rules
+/// (including the shipped defaults from `default_rules`) without
+/// recompiling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionRule {
+    pub name: String,
+    pub matcher: RuleMatcher,
+    pub action: SuggestionAction,
+    pub confidence: f32,
+    pub reason: String,
+    pub category: String,
+    pub enabled: bool,
+}
+
+/// The built-in rules shipped by default, equivalent to the hardcoded
+/// checks `analyze_document` used to perform directly. Users can disable
+/// or override any of these through the rule-management commands without
+/// losing the out-of-the-box behavior.
+pub fn default_rules() -> Vec<SuggestionRule> {
+    let downloads = || RuleMatcher::ParentDirContains(vec!["downloads".to_string()]);
+
+    vec![
+        SuggestionRule {
+            name: "downloads-resume".to_string(),
+            matcher: RuleMatcher::All(vec![downloads(), RuleMatcher::FilenameContains(vec!["resume".to_string(), "cv".to_string()])]),
+            action: SuggestionAction::Move { to_path: get_documents_path("Career/Resumes") },
+            confidence: 0.75,
+            reason: "File in Downloads - move to Resumes folder?".to_string(),
+            category: "Resumes".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "downloads-finance".to_string(),
+            matcher: RuleMatcher::All(vec![downloads(), RuleMatcher::FilenameContains(vec!["receipt".to_string(), "invoice".to_string(), "statement".to_string()])]),
+            action: SuggestionAction::Move { to_path: get_documents_path("Finance/Receipts") },
+            confidence: 0.75,
+            reason: "File in Downloads - move to Finance folder?".to_string(),
+            category: "Finance".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "downloads-school".to_string(),
+            matcher: RuleMatcher::All(vec![downloads(), RuleMatcher::FilenameContains(vec!["report".to_string(), "homework".to_string(), "assignment".to_string()])]),
+            action: SuggestionAction::Move { to_path: get_documents_path("School") },
+            confidence: 0.75,
+            reason: "File in Downloads - move to School folder?".to_string(),
+            category: "School".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "downloads-legal".to_string(),
+            matcher: RuleMatcher::All(vec![downloads(), RuleMatcher::FilenameContains(vec!["contract".to_string(), "agreement".to_string(), "legal".to_string()])]),
+            action: SuggestionAction::Move { to_path: get_documents_path("Legal") },
+            confidence: 0.75,
+            reason: "File in Downloads - move to Legal folder?".to_string(),
+            category: "Legal".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "downloads-word".to_string(),
+            matcher: RuleMatcher::All(vec![downloads(), RuleMatcher::DocType(DocumentType::Word)]),
+            action: SuggestionAction::Move { to_path: get_documents_path("Documents/Word") },
+            confidence: 0.6,
+            reason: "File in Downloads - move to Documents folder?".to_string(),
+            category: "Documents".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "downloads-excel".to_string(),
+            matcher: RuleMatcher::All(vec![downloads(), RuleMatcher::DocType(DocumentType::Excel)]),
+            action: SuggestionAction::Move { to_path: get_documents_path("Documents/Spreadsheets") },
+            confidence: 0.6,
+            reason: "File in Downloads - move to Documents folder?".to_string(),
+            category: "Spreadsheets".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "downloads-powerpoint".to_string(),
+            matcher: RuleMatcher::All(vec![downloads(), RuleMatcher::DocType(DocumentType::PowerPoint)]),
+            action: SuggestionAction::Move { to_path: get_documents_path("Documents/Presentations") },
+            confidence: 0.6,
+            reason: "File in Downloads - move to Documents folder?".to_string(),
+            category: "Presentations".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "downloads-pdf".to_string(),
+            matcher: RuleMatcher::All(vec![downloads(), RuleMatcher::DocType(DocumentType::PDF)]),
+            action: SuggestionAction::Move { to_path: get_documents_path("Documents/PDFs") },
+            confidence: 0.6,
+            reason: "File in Downloads - move to Documents folder?".to_string(),
+            category: "PDFs".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "downloads-misc".to_string(),
+            matcher: downloads(),
+            action: SuggestionAction::Move { to_path: get_documents_path("Documents/Misc") },
+            confidence: 0.5,
+            reason: "File in Downloads - move to Misc folder?".to_string(),
+            category: "Misc".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "resume".to_string(),
+            matcher: RuleMatcher::FilenameContains(vec!["resume".to_string(), "cv".to_string()]),
+            action: SuggestionAction::Move { to_path: get_documents_path("Career/Resumes") },
+            confidence: 0.9,
+            reason: "Resume detected - keep with other career documents".to_string(),
+            category: "Resumes".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "receipt".to_string(),
+            matcher: RuleMatcher::FilenameContains(vec!["receipt".to_string(), "invoice".to_string()]),
+            action: SuggestionAction::Move { to_path: get_documents_path("Finance/Receipts") },
+            confidence: 0.85,
+            reason: "Receipt/invoice detected - organize with financial documents".to_string(),
+            category: "Receipts".to_string(),
+            enabled: true,
+        },
+        SuggestionRule {
+            name: "poorly-named".to_string(),
+            matcher: RuleMatcher::PoorlyNamed,
+            action: SuggestionAction::Rename { new_name: String::new() },
+            confidence: 0.7,
+            reason: "Generic filename - consider a more descriptive name".to_string(),
+            category: "Naming".to_string(),
+            enabled: true,
+        },
+    ]
 }
 
 /// Naming pattern detected from user's files
@@ -125,13 +310,27 @@ pub enum PatternType {
 }
 
 /// User's learned preferences
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserPreferences {
     pub preferred_naming: Option<PatternType>,
     pub preferred_structure: FolderStructure,
     pub dismissed_suggestions: Vec<String>,  // File paths that user said "leave alone"
     pub custom_categories: HashMap<String, String>,  // "Resumes" -> "~/Documents/Career/Resumes"
     pub suggestion_frequency: SuggestionFrequency,
+    pub rules: Vec<SuggestionRule>,  // Seeded with `default_rules()`; the highest-confidence match wins, not list order
+}
+
+impl Default for UserPreferences {
+    fn default() -> Self {
+        UserPreferences {
+            preferred_naming: None,
+            preferred_structure: FolderStructure::default(),
+            dismissed_suggestions: Vec::new(),
+            custom_categories: HashMap::new(),
+            suggestion_frequency: SuggestionFrequency::default(),
+            rules: default_rules(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
@@ -169,106 +368,251 @@ pub struct ScanStatistics {
 // CORE FUNCTIONS
 // ============================================================================
 
-/// Scan a directory for organizable documents
-pub fn scan_for_documents(root_path: &str, max_depth: Option<usize>) -> Result<Vec<DiscoveredDocument>, String> {
+/// Scan a directory for organizable documents. `include`/`exclude` are
+/// gitignore-style glob patterns (e.g. `/home/me/Documents/**/*.pdf`,
+/// `**/node_modules/**`): excludes prune a matched directory's whole
+/// subtree during the walk rather than being filtered out afterward, and
+/// win over includes on conflict. An include's base directory (its path
+/// up to the first wildcard component) is walked directly instead of
+/// expanding the whole root, so an include scoped to one subtree doesn't
+/// pay to walk unrelated ones.
+pub fn scan_for_documents(
+    root_path: &str,
+    max_depth: Option<usize>,
+    include: &[String],
+    exclude: &[String],
+) -> Result<Vec<DiscoveredDocument>, String> {
+    scan_for_documents_with_progress(root_path, max_depth, include, exclude, None, None)
+}
+
+/// Shared state threaded through `scan_dir_recursive`'s parallel fan-out: a
+/// running total of documents discovered so far, an optional callback fired
+/// with the new total each time it grows, and an optional cancellation
+/// check polled between directories.
+struct ScanProgress<'a> {
+    found: AtomicUsize,
+    on_progress: Option<&'a (dyn Fn(usize) + Sync)>,
+    cancel: Option<&'a (dyn Fn() -> bool + Sync)>,
+}
+
+impl ScanProgress<'_> {
+    fn is_canceled(&self) -> bool {
+        self.cancel.is_some_and(|c| c())
+    }
+
+    fn report(&self, newly_found: usize) {
+        if newly_found == 0 {
+            return;
+        }
+        let total = self.found.fetch_add(newly_found, Ordering::Relaxed) + newly_found;
+        if let Some(cb) = self.on_progress {
+            cb(total);
+        }
+    }
+}
+
+/// Same as `scan_for_documents`, but reports the running count of documents
+/// discovered so far through `on_progress` as the walk proceeds (subdirectories
+/// are walked in parallel, so calls may arrive out of order — only the latest
+/// total matters), and checks `cancel` between directories so a queued scan
+/// task can be stopped early without finishing the whole tree.
+pub fn scan_for_documents_with_progress(
+    root_path: &str,
+    max_depth: Option<usize>,
+    include: &[String],
+    exclude: &[String],
+    on_progress: Option<&(dyn Fn(usize) + Sync)>,
+    cancel: Option<&(dyn Fn() -> bool + Sync)>,
+) -> Result<Vec<DiscoveredDocument>, String> {
     let root = Path::new(root_path);
     if !root.exists() {
         return Err(format!("Path does not exist: {}", root_path));
     }
-    
-    let mut documents = Vec::new();
+
+    let include_set = if include.is_empty() { None } else { Some(build_glob_set(include)?) };
+    let exclude_set = if exclude.is_empty() { None } else { Some(build_glob_set(exclude)?) };
     let max_d = max_depth.unwrap_or(10);
-    
-    // Track sibling counts per directory
-    let mut dir_file_counts: HashMap<PathBuf, (usize, HashMap<String, usize>)> = HashMap::new();
-    
-    // First pass: count files per directory
-    for entry in WalkDir::new(root)
-        .max_depth(max_d)
-        .follow_links(false)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        if entry.file_type().is_file() {
-            if let Some(parent) = entry.path().parent() {
-                let ext = entry.path()
-                    .extension()
-                    .map(|e| e.to_string_lossy().to_lowercase())
-                    .unwrap_or_default();
-                
-                let (count, ext_counts) = dir_file_counts
-                    .entry(parent.to_path_buf())
-                    .or_insert((0, HashMap::new()));
-                *count += 1;
-                *ext_counts.entry(ext).or_insert(0) += 1;
-            }
-        }
-    }
-    
-    // Second pass: collect documents
-    for entry in WalkDir::new(root)
-        .max_depth(max_d)
-        .follow_links(false)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        if !entry.file_type().is_file() {
+    let progress = ScanProgress { found: AtomicUsize::new(0), on_progress, cancel };
+
+    let mut documents = Vec::new();
+    for base in include_base_dirs(root, include) {
+        if !base.exists() {
             continue;
         }
-        
-        let path = entry.path();
-        let ext = path
-            .extension()
-            .map(|e| e.to_string_lossy().to_lowercase())
-            .unwrap_or_default();
-        
-        let doc_type = DocumentType::from_extension(&ext);
-        
-        // Skip code files and unknown types for organization
-        if !doc_type.is_organizable() {
-            continue;
+        if progress.is_canceled() {
+            break;
         }
-        
-        let metadata = entry.metadata().ok();
-        let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
-        let modified = metadata
-            .and_then(|m| m.modified().ok())
-            .map(|t| {
-                let dt: DateTime<Local> = t.into();
-                dt.format("%Y-%m-%d %H:%M:%S").to_string()
-            })
-            .unwrap_or_else(|| "unknown".to_string());
-        
-        let parent = path.parent().unwrap_or(Path::new(""));
-        let depth = path.components().count() - root.components().count();
-        
-        let (siblings, ext_counts) = dir_file_counts
-            .get(&parent.to_path_buf())
-            .cloned()
-            .unwrap_or((0, HashMap::new()));
-        
-        let similar = ext_counts.get(&ext).cloned().unwrap_or(0);
-        
-        documents.push(DiscoveredDocument {
-            path: path.to_string_lossy().to_string(),
-            name: path.file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default(),
-            extension: ext,
-            doc_type,
-            size_bytes: size,
-            modified,
-            parent_dir: parent.to_string_lossy().to_string(),
-            depth,
-            siblings_count: siblings,
-            similar_siblings: similar,
-        });
+        let base_depth = base.components().count().saturating_sub(root.components().count());
+        let remaining_depth = max_d.saturating_sub(base_depth);
+        documents.extend(scan_dir_recursive(&base, root, remaining_depth, include_set.as_ref(), exclude_set.as_ref(), &progress));
     }
-    
+
+    // Overlapping include bases can walk the same file twice.
+    documents.sort_by(|a, b| a.path.cmp(&b.path));
+    documents.dedup_by(|a, b| a.path == b.path);
+
     println!("[FILE_INTEL] Scanned {} organizable documents", documents.len());
     Ok(documents)
 }
 
+/// Compile `patterns` into a single `GlobSet`.
+fn build_glob_set(patterns: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| e.to_string())?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// The literal (non-wildcard) prefix of each include pattern, resolved
+/// against `root`, so the walk can start there directly instead of
+/// expanding the whole root and discarding non-matches afterward. Falls
+/// back to `root` itself when there's no include list or a pattern has no
+/// literal prefix.
+fn include_base_dirs(root: &Path, include: &[String]) -> Vec<PathBuf> {
+    if include.is_empty() {
+        return vec![root.to_path_buf()];
+    }
+
+    include
+        .iter()
+        .map(|pattern| {
+            let is_wildcard = |s: &std::ffi::OsStr| {
+                let s = s.to_string_lossy();
+                s.contains('*') || s.contains('?') || s.contains('[')
+            };
+            let literal_prefix: PathBuf = Path::new(pattern)
+                .components()
+                .take_while(|c| !is_wildcard(c.as_os_str()))
+                .collect();
+
+            if literal_prefix.as_os_str().is_empty() {
+                root.to_path_buf()
+            } else if literal_prefix.is_absolute() {
+                literal_prefix
+            } else {
+                root.join(literal_prefix)
+            }
+        })
+        .collect()
+}
+
+/// Scan a single directory's own entries (computing its sibling and
+/// per-extension counts locally, in the same pass, instead of a separate
+/// pre-pass over the whole tree), then fan out into its subdirectories in
+/// parallel via `par_iter`. Symlinks are skipped rather than followed,
+/// matching the old `WalkDir::follow_links(false)` behavior and avoiding
+/// cycles; `remaining_depth` is how many more levels (including this
+/// directory's own files) are still allowed, and reaching zero stops the
+/// walk before even listing this directory's files — matching
+/// `WalkDir::max_depth(0)` yielding zero files, not "this directory's
+/// files plus one more level of subdirectories".
+fn scan_dir_recursive(
+    dir: &Path,
+    root: &Path,
+    remaining_depth: usize,
+    include: Option<&GlobSet>,
+    exclude: Option<&GlobSet>,
+    progress: &ScanProgress,
+) -> Vec<DiscoveredDocument> {
+    if progress.is_canceled() || remaining_depth == 0 {
+        return Vec::new();
+    }
+
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files = Vec::new();
+    let mut subdirs = Vec::new();
+
+    for entry in read_dir.filter_map(|e| e.ok()) {
+        let Ok(file_type) = entry.file_type() else { continue };
+        let path = entry.path();
+
+        if let Some(exclude) = exclude
+            && exclude.is_match(&path)
+        {
+            continue;
+        }
+
+        if file_type.is_file() {
+            files.push(path);
+        } else if file_type.is_dir() {
+            subdirs.push(path);
+        }
+    }
+
+    let siblings = files.len();
+    let mut ext_counts: HashMap<String, usize> = HashMap::new();
+    for file in &files {
+        let ext = file.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        *ext_counts.entry(ext).or_insert(0) += 1;
+    }
+
+    let mut documents: Vec<DiscoveredDocument> = files
+        .into_iter()
+        .filter(|path| include.is_none_or(|set| set.is_match(path)))
+        .filter_map(|path| document_from_file(&path, root, siblings, &ext_counts))
+        .collect();
+
+    progress.report(documents.len());
+
+    let nested: Vec<DiscoveredDocument> = subdirs
+        .par_iter()
+        .flat_map(|subdir| scan_dir_recursive(subdir, root, remaining_depth - 1, include, exclude, progress))
+        .collect();
+
+    documents.extend(nested);
+    documents
+}
+
+/// Classify a single file into a `DiscoveredDocument`, or `None` if it
+/// isn't an organizable type. `siblings`/`ext_counts` come from the
+/// directory scan that found it.
+fn document_from_file(
+    path: &Path,
+    root: &Path,
+    siblings: usize,
+    ext_counts: &HashMap<String, usize>,
+) -> Option<DiscoveredDocument> {
+    let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let doc_type = DocumentType::from_extension(&ext);
+
+    // Skip code files and unknown types for organization
+    if !doc_type.is_organizable() {
+        return None;
+    }
+
+    let metadata = fs::metadata(path).ok();
+    let size = metadata.as_ref().map(|m| m.len()).unwrap_or(0);
+    let modified = metadata
+        .and_then(|m| m.modified().ok())
+        .map(|t| {
+            let dt: DateTime<Local> = t.into();
+            dt.format("%Y-%m-%d %H:%M:%S").to_string()
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let depth = path.components().count() - root.components().count();
+    let similar = ext_counts.get(&ext).cloned().unwrap_or(0);
+
+    Some(DiscoveredDocument {
+        path: path.to_string_lossy().to_string(),
+        name: path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        extension: ext,
+        doc_type,
+        size_bytes: size,
+        modified,
+        parent_dir: parent.to_string_lossy().to_string(),
+        depth,
+        siblings_count: siblings,
+        similar_siblings: similar,
+    })
+}
+
 /// Analyze documents and generate organization suggestions
 pub fn generate_suggestions(documents: &[DiscoveredDocument], preferences: &UserPreferences) -> Vec<OrganizationSuggestion> {
     let mut suggestions = Vec::new();
@@ -285,71 +629,142 @@ pub fn generate_suggestions(documents: &[DiscoveredDocument], preferences: &User
         }
     }
     
+    for group in find_duplicate_groups(documents) {
+        let keeper = pick_keeper(&group);
+        for doc in &group {
+            if std::ptr::eq(*doc, keeper) || preferences.dismissed_suggestions.contains(&doc.path) {
+                continue;
+            }
+            suggestions.push(OrganizationSuggestion {
+                file_path: doc.path.clone(),
+                file_name: doc.name.clone(),
+                action: SuggestionAction::DeleteDuplicate {
+                    keep_path: keeper.path.clone(),
+                    duplicate_of: keeper.path.clone(),
+                },
+                confidence: 0.95,
+                reason: format!("Identical content to {}", keeper.path),
+                category: "Duplicates".to_string(),
+            });
+        }
+    }
+
     // Sort by confidence (highest first)
     suggestions.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
-    
+
     suggestions
 }
 
-/// Analyze a single document and maybe suggest an action
+/// Normalize a path for case-insensitive comparison, so two paths
+/// differing only in case (as on a case-insensitive filesystem) are
+/// recognized as the same on-disk file rather than reported as a
+/// duplicate of itself.
+fn normalize_path_key(path: &str) -> String {
+    path.to_lowercase()
+}
+
+/// Find groups of files with identical content among `documents`, without
+/// hashing everything: first group by `size_bytes` (cheap), then only
+/// content-hash files within a size-group that has more than one member,
+/// grouping by digest. Files are hashed by streaming through the hasher
+/// rather than loading them into memory, so large files stay bounded.
+fn find_duplicate_groups(documents: &[DiscoveredDocument]) -> Vec<Vec<&DiscoveredDocument>> {
+    let mut by_path: HashMap<String, &DiscoveredDocument> = HashMap::new();
+    for doc in documents {
+        by_path.entry(normalize_path_key(&doc.path)).or_insert(doc);
+    }
+
+    let mut by_size: HashMap<u64, Vec<&DiscoveredDocument>> = HashMap::new();
+    for doc in by_path.into_values() {
+        by_size.entry(doc.size_bytes).or_default().push(doc);
+    }
+
+    let mut groups = Vec::new();
+    for candidates in by_size.into_values() {
+        if candidates.len() < 2 {
+            continue;
+        }
+
+        let mut by_hash: HashMap<String, Vec<&DiscoveredDocument>> = HashMap::new();
+        for doc in candidates {
+            if let Some(digest) = hash_file_contents(&doc.path) {
+                by_hash.entry(digest).or_default().push(doc);
+            }
+        }
+
+        groups.extend(by_hash.into_values().filter(|group| group.len() > 1));
+    }
+
+    groups
+}
+
+/// Stream a file's bytes through blake3 rather than reading it into
+/// memory, so hashing large files doesn't blow up the scan's memory use.
+fn hash_file_contents(path: &str) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(hasher.finalize().to_hex().to_string())
+}
+
+/// Pick which file in a duplicate group to keep: the shallowest path (the
+/// one closest to the scan root), breaking ties in favor of the more
+/// descriptive (not "poorly named") filename, then lexicographically for a
+/// stable result.
+fn pick_keeper<'a>(group: &[&'a DiscoveredDocument]) -> &'a DiscoveredDocument {
+    group
+        .iter()
+        .min_by(|a, b| {
+            a.depth
+                .cmp(&b.depth)
+                .then_with(|| is_poorly_named(&a.name).cmp(&is_poorly_named(&b.name)))
+                .then_with(|| a.path.cmp(&b.path))
+        })
+        .copied()
+        .expect("duplicate group is never empty")
+}
+
+/// Analyze a single document and maybe suggest an action. Iterates the
+/// registered rules (`preferences.rules`, seeded from `default_rules` but
+/// user-extensible) in confidence order and builds a suggestion from the
+/// first/highest-confidence match.
 fn analyze_document(doc: &DiscoveredDocument, preferences: &UserPreferences) -> Option<OrganizationSuggestion> {
-    let name_lower = doc.name.to_lowercase();
-    let parent_lower = doc.parent_dir.to_lowercase();
-    
     // Check if it's in project directory (has code files, git, etc.)
     if is_project_directory(&doc.parent_dir) {
         return None; // Leave project files alone
     }
-    
-    // Check if file is in Downloads
-    if parent_lower.contains("downloads") {
-        return suggest_from_downloads(doc, preferences);
-    }
-    
-    // Check for resume/CV
-    if name_lower.contains("resume") || name_lower.contains("cv") {
-        return Some(OrganizationSuggestion {
-            file_path: doc.path.clone(),
-            file_name: doc.name.clone(),
-            action: SuggestionAction::Move {
-                to_path: get_documents_path("Career/Resumes"),
-            },
-            confidence: 0.9,
-            reason: "Resume detected - keep with other career documents".to_string(),
-            category: "Resumes".to_string(),
-        });
-    }
-    
-    // Check for receipts
-    if name_lower.contains("receipt") || name_lower.contains("invoice") {
-        return Some(OrganizationSuggestion {
-            file_path: doc.path.clone(),
-            file_name: doc.name.clone(),
-            action: SuggestionAction::Move {
-                to_path: get_documents_path("Finance/Receipts"),
-            },
-            confidence: 0.85,
-            reason: "Receipt/invoice detected - organize with financial documents".to_string(),
-            category: "Receipts".to_string(),
-        });
-    }
-    
-    // Check for poorly named files
-    if is_poorly_named(&doc.name) {
-        return Some(OrganizationSuggestion {
-            file_path: doc.path.clone(),
-            file_name: doc.name.clone(),
-            action: SuggestionAction::Rename {
-                new_name: suggest_better_name(doc),
-            },
-            confidence: 0.7,
-            reason: "Generic filename - consider a more descriptive name".to_string(),
-            category: "Naming".to_string(),
-        });
+
+    let rule = preferences
+        .rules
+        .iter()
+        .filter(|rule| rule.enabled && rule.matcher.matches(doc))
+        .max_by(|a, b| a.confidence.partial_cmp(&b.confidence).unwrap_or(std::cmp::Ordering::Equal))?;
+
+    Some(OrganizationSuggestion {
+        file_path: doc.path.clone(),
+        file_name: doc.name.clone(),
+        action: resolve_rule_action(&rule.action, &rule.category, doc, preferences),
+        confidence: rule.confidence,
+        reason: rule.reason.clone(),
+        category: rule.category.clone(),
+    })
+}
+
+/// Turn a rule's template action into a concrete one for `doc`: a `Move`'s
+/// destination resolves through `preferences.custom_categories` first (so
+/// a user-defined category destination overrides the rule's own default),
+/// and a `Rename`'s name is always computed fresh since it depends on the
+/// specific file being renamed.
+fn resolve_rule_action(action: &SuggestionAction, category: &str, doc: &DiscoveredDocument, preferences: &UserPreferences) -> SuggestionAction {
+    match action {
+        SuggestionAction::Move { to_path } => SuggestionAction::Move {
+            to_path: preferences.custom_categories.get(category).cloned().unwrap_or_else(|| to_path.clone()),
+        },
+        SuggestionAction::Rename { .. } => SuggestionAction::Rename {
+            new_name: suggest_better_name(doc),
+        },
+        other => other.clone(),
     }
-    
-    // File is in a good place
-    None
 }
 
 /// Check if directory is a code project (don't move files from here)
@@ -389,42 +804,6 @@ fn is_project_directory(path: &str) -> bool {
     false
 }
 
-/// Suggest what to do with files in Downloads
-fn suggest_from_downloads(doc: &DiscoveredDocument, _preferences: &UserPreferences) -> Option<OrganizationSuggestion> {
-    let name_lower = doc.name.to_lowercase();
-    
-    // Determine category based on content/name
-    let (category, dest) = if name_lower.contains("resume") || name_lower.contains("cv") {
-        ("Resumes", "Career/Resumes")
-    } else if name_lower.contains("receipt") || name_lower.contains("invoice") || name_lower.contains("statement") {
-        ("Finance", "Finance/Receipts")
-    } else if name_lower.contains("report") || name_lower.contains("homework") || name_lower.contains("assignment") {
-        ("School", "School")
-    } else if name_lower.contains("contract") || name_lower.contains("agreement") || name_lower.contains("legal") {
-        ("Legal", "Legal")
-    } else {
-        // Generic suggestion based on file type
-        match doc.doc_type {
-            DocumentType::Word => ("Documents", "Documents/Word"),
-            DocumentType::Excel => ("Spreadsheets", "Documents/Spreadsheets"),
-            DocumentType::PowerPoint => ("Presentations", "Documents/Presentations"),
-            DocumentType::PDF => ("PDFs", "Documents/PDFs"),
-            _ => ("Misc", "Documents/Misc"),
-        }
-    };
-    
-    Some(OrganizationSuggestion {
-        file_path: doc.path.clone(),
-        file_name: doc.name.clone(),
-        action: SuggestionAction::Move {
-            to_path: get_documents_path(dest),
-        },
-        confidence: 0.75,
-        reason: format!("File in Downloads - move to {} folder?", category),
-        category: category.to_string(),
-    })
-}
-
 /// Check if a filename is poorly named (generic)
 fn is_poorly_named(name: &str) -> bool {
     let generic_patterns = [
@@ -601,11 +980,16 @@ pub fn calculate_statistics(documents: &[DiscoveredDocument]) -> ScanStatistics
         }
     };
     
+    let potential_duplicates: usize = find_duplicate_groups(documents)
+        .iter()
+        .map(|group| group.len() - 1)
+        .sum();
+
     ScanStatistics {
         total_documents: documents.len(),
         by_type,
         by_location,
-        potential_duplicates: 0,  // TODO: implement duplicate detection
+        potential_duplicates,
         unorganized_count: unorganized,
         naming_score,
     }
@@ -643,4 +1027,156 @@ mod tests {
         assert_eq!(classify_name_pattern("report-v3"), PatternType::VersionNumbered);
         assert_eq!(classify_name_pattern("notes-meeting-budget"), PatternType::CategoryFirst);
     }
+
+    fn test_dir() -> std::path::PathBuf {
+        static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!("file_intel_test_{}_{}", std::process::id(), id));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn exclude_prunes_matched_directories() {
+        let dir = test_dir();
+        fs::write(dir.join("report.pdf"), b"pdf").unwrap();
+        fs::create_dir_all(dir.join("node_modules")).unwrap();
+        fs::write(dir.join("node_modules").join("ignored.pdf"), b"pdf").unwrap();
+
+        let exclude = vec!["**/node_modules/**".to_string()];
+        let documents = scan_for_documents(&dir.to_string_lossy(), None, &[], &exclude).unwrap();
+
+        assert_eq!(documents.len(), 1);
+        assert!(documents[0].path.ends_with("report.pdf"));
+    }
+
+    #[test]
+    fn max_depth_zero_finds_nothing() {
+        let dir = test_dir();
+        fs::write(dir.join("report.pdf"), b"pdf").unwrap();
+
+        let documents = scan_for_documents(&dir.to_string_lossy(), Some(0), &[], &[]).unwrap();
+
+        assert!(documents.is_empty());
+    }
+
+    #[test]
+    fn max_depth_one_excludes_nested_files() {
+        let dir = test_dir();
+        fs::write(dir.join("report.pdf"), b"pdf").unwrap();
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("nested").join("deep.pdf"), b"pdf").unwrap();
+
+        let documents = scan_for_documents(&dir.to_string_lossy(), Some(1), &[], &[]).unwrap();
+
+        assert_eq!(documents.len(), 1);
+        assert!(documents[0].path.ends_with("report.pdf"));
+    }
+
+    #[test]
+    fn include_restricts_to_matching_files() {
+        let dir = test_dir();
+        fs::write(dir.join("report.pdf"), b"pdf").unwrap();
+        fs::write(dir.join("notes.docx"), b"docx").unwrap();
+
+        let include = vec!["**/*.pdf".to_string()];
+        let documents = scan_for_documents(&dir.to_string_lossy(), None, &include, &[]).unwrap();
+
+        assert_eq!(documents.len(), 1);
+        assert!(documents[0].path.ends_with("report.pdf"));
+    }
+
+    #[test]
+    fn finds_duplicate_content_regardless_of_size_group_siblings() {
+        let dir = test_dir();
+        fs::write(dir.join("a.txt"), b"same content").unwrap();
+        fs::write(dir.join("b.txt"), b"same content").unwrap();
+        fs::write(dir.join("c.txt"), b"different content entirely").unwrap();
+
+        let documents = scan_for_documents(&dir.to_string_lossy(), None, &[], &[]).unwrap();
+        let groups = find_duplicate_groups(&documents);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 2);
+
+        let stats = calculate_statistics(&documents);
+        assert_eq!(stats.potential_duplicates, 1);
+    }
+
+    #[test]
+    fn prefers_shallower_path_when_picking_which_duplicate_to_keep() {
+        let dir = test_dir();
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("top.txt"), b"dup content").unwrap();
+        fs::write(dir.join("nested").join("deep.txt"), b"dup content").unwrap();
+
+        let documents = scan_for_documents(&dir.to_string_lossy(), None, &[], &[]).unwrap();
+        let groups = find_duplicate_groups(&documents);
+        let keeper = pick_keeper(&groups[0]);
+
+        assert!(keeper.path.ends_with("top.txt"));
+    }
+
+    fn sample_doc(path: &str, parent_dir: &str) -> DiscoveredDocument {
+        DiscoveredDocument {
+            path: path.to_string(),
+            name: Path::new(path).file_name().unwrap().to_string_lossy().to_string(),
+            extension: Path::new(path).extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default(),
+            doc_type: DocumentType::PDF,
+            size_bytes: 100,
+            modified: "2026-01-01 00:00:00".to_string(),
+            parent_dir: parent_dir.to_string(),
+            depth: 1,
+            siblings_count: 1,
+            similar_siblings: 1,
+        }
+    }
+
+    #[test]
+    fn rule_registry_matches_and_resolves_custom_category_destination() {
+        let doc = sample_doc("/home/user/Documents/my_resume.pdf", "/home/user/Documents");
+        let mut preferences = UserPreferences::default();
+        preferences.custom_categories.insert("Resumes".to_string(), "/home/user/Career/CVs".to_string());
+
+        let suggestion = analyze_document(&doc, &preferences).expect("resume rule should match");
+
+        assert_eq!(suggestion.category, "Resumes");
+        match suggestion.action {
+            SuggestionAction::Move { to_path } => assert_eq!(to_path, "/home/user/Career/CVs"),
+            other => panic!("expected Move, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn disabling_a_rule_stops_it_from_matching() {
+        let doc = sample_doc("/home/user/Documents/my_resume.pdf", "/home/user/Documents");
+        let mut preferences = UserPreferences::default();
+        for rule in preferences.rules.iter_mut() {
+            if rule.name == "resume" {
+                rule.enabled = false;
+            }
+        }
+
+        let suggestion = analyze_document(&doc, &preferences);
+        assert!(suggestion.is_none());
+    }
+
+    #[test]
+    fn user_defined_rule_can_outrank_built_ins() {
+        let doc = sample_doc("/home/user/Documents/my_resume.pdf", "/home/user/Documents");
+        let mut preferences = UserPreferences::default();
+        preferences.rules.push(SuggestionRule {
+            name: "custom-resume-override".to_string(),
+            matcher: RuleMatcher::FilenameContains(vec!["resume".to_string()]),
+            action: SuggestionAction::Archive,
+            confidence: 0.99,
+            reason: "User override".to_string(),
+            category: "CustomArchive".to_string(),
+            enabled: true,
+        });
+
+        let suggestion = analyze_document(&doc, &preferences).unwrap();
+        assert_eq!(suggestion.category, "CustomArchive");
+        assert!(matches!(suggestion.action, SuggestionAction::Archive));
+    }
 }
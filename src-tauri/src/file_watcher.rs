@@ -1,17 +1,21 @@
 // File Watcher Service
 // Watches directories for new/modified files and triggers suggestions
 
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use serde::{Deserialize, Serialize};
 
 use crate::file_intelligence::{DocumentType, DiscoveredDocument};
 
+const IGNORE_FILE_NAMES: [&str; 2] = [".gitignore", ".ignore"];
+
 // ============================================================================
 // TYPES
 // ============================================================================
@@ -33,13 +37,33 @@ pub enum FileEventType {
     Renamed { from: String },
 }
 
+/// Which directory-watching backend to use for a given path.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum WatchBackend {
+    /// Try the OS-native backend first, falling back to polling for any
+    /// path whose native registration fails (network shares, some WSL or
+    /// virtualized mounts).
+    #[default]
+    Auto,
+    /// OS-native only (inotify/FSEvents/ReadDirectoryChangesW).
+    Native,
+    /// Poll-only: stat the tree on `poll_interval_ms` and diff snapshots.
+    Poll,
+}
+
 /// Configuration for watching
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WatchConfig {
     pub paths: Vec<String>,
     pub debounce_ms: u64,           // Wait this long before firing event
-    pub ignore_patterns: Vec<String>,
     pub watch_only_organizable: bool,
+    pub backend: WatchBackend,
+    pub poll_interval_ms: u64,      // Only used by the Poll backend
+    pub rename_window_ms: u64,      // Window to pair a Remove with a later Create as a rename
+    #[serde(default)]
+    pub include_patterns: Vec<String>, // Glob patterns; empty means "everything passes"
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>, // Glob patterns; checked before include_patterns
 }
 
 impl Default for WatchConfig {
@@ -47,16 +71,12 @@ impl Default for WatchConfig {
         WatchConfig {
             paths: get_default_watch_paths(),
             debounce_ms: 2000,  // 2 second debounce
-            ignore_patterns: vec![
-                ".git".to_string(),
-                "node_modules".to_string(),
-                "__pycache__".to_string(),
-                ".vscode".to_string(),
-                "target".to_string(),
-                ".tmp".to_string(),
-                "~$".to_string(),  // Office temp files
-            ],
             watch_only_organizable: true,
+            backend: WatchBackend::Auto,
+            poll_interval_ms: 3000,
+            rename_window_ms: 2000,
+            include_patterns: Vec::new(),
+            exclude_patterns: Vec::new(),
         }
     }
 }
@@ -80,6 +100,506 @@ impl Default for FileWatcherState {
     }
 }
 
+// ============================================================================
+// IGNORE RULE ENGINE
+// ============================================================================
+
+/// Baseline rules applied under every watched root before any `.gitignore`/
+/// `.ignore` file is layered on top, so directories with no ignore files of
+/// their own still skip the obvious noise.
+const BUILTIN_IGNORE_RULES: [&str; 7] = [
+    ".git/",
+    "node_modules/",
+    "__pycache__/",
+    ".vscode/",
+    "target/",
+    "*.tmp",
+    "~$*", // Office temp files
+];
+
+/// Per-root cache of compiled ignore matchers. Each watched root gets a
+/// single `Gitignore`, rooted at the watched root, built by layering the
+/// baseline rules under every `.gitignore`/`.ignore` file found from the
+/// root down, with later rules (and negations) overriding earlier ones.
+/// Patterns from a nested ignore file are matched relative to the watched
+/// root, not to that file's own directory, so they apply across the root.
+#[derive(Default)]
+struct IgnoreEngine {
+    roots: Mutex<HashMap<PathBuf, Gitignore>>,
+}
+
+impl IgnoreEngine {
+    fn new() -> Self {
+        IgnoreEngine { roots: Mutex::new(HashMap::new()) }
+    }
+
+    /// (Re)build and cache the matcher for a watched root.
+    fn build_root(&self, root: &Path) {
+        let matcher = compile_root(root);
+        if let Ok(mut roots) = self.roots.lock() {
+            roots.insert(root.to_path_buf(), matcher);
+        }
+    }
+
+    /// Drop and recompile the cached matcher for `root`. Called when one of
+    /// its `.gitignore`/`.ignore` files itself fires a Modify event.
+    fn invalidate(&self, root: &Path) {
+        self.build_root(root);
+    }
+
+    /// True if `path` (somewhere under `root`) is ignored by the root's
+    /// compiled rules.
+    fn is_ignored(&self, root: &Path, path: &Path, is_dir: bool) -> bool {
+        let roots = match self.roots.lock() {
+            Ok(r) => r,
+            Err(_) => return false,
+        };
+        match roots.get(root) {
+            Some(matcher) => matcher.matched(path, is_dir).is_ignore(),
+            None => false,
+        }
+    }
+}
+
+/// Walk `root` collecting every `.gitignore`/`.ignore` file and compile them
+/// into a single matcher alongside the built-in baseline. Files are added in
+/// depth-first order, which keeps each ancestor chain's rules in root-to-leaf
+/// order (the order that matters for `GitignoreBuilder` precedence) even
+/// though unrelated sibling subtrees may interleave.
+fn compile_root(root: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    for pattern in BUILTIN_IGNORE_RULES {
+        let _ = builder.add_line(None, pattern);
+    }
+
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+
+            if let Some(name) = path.file_name().and_then(|n| n.to_str())
+                && IGNORE_FILE_NAMES.contains(&name)
+                && let Some(err) = builder.add(&path)
+            {
+                eprintln!("[FILE_WATCHER] Failed to parse {}: {}", path.display(), err);
+            }
+        }
+    }
+
+    builder.build().unwrap_or_else(|e| {
+        eprintln!("[FILE_WATCHER] Failed to compile ignore rules for {}: {}", root.display(), e);
+        Gitignore::empty()
+    })
+}
+
+/// Find which configured watch root is an ancestor of `path`, so only that
+/// root's ignore rules (not some other watched root's) get evaluated.
+fn find_watch_root(path: &Path, roots: &[String]) -> Option<PathBuf> {
+    roots.iter()
+        .map(Path::new)
+        .find(|root| path.starts_with(root))
+        .map(|root| root.to_path_buf())
+}
+
+// ============================================================================
+// PATTERN FILTERS
+// ============================================================================
+
+/// User-configured glob include/exclude filters from `WatchConfig`, layered
+/// on top of the gitignore-based `IgnoreEngine`. Held behind a lock (rather
+/// than compiled once at startup) so `WatchHandle::set_filters` can swap in
+/// a recompiled set live, without restarting the watcher.
+struct FilterState {
+    include: Mutex<Option<GlobSet>>,
+    exclude: Mutex<Option<GlobSet>>,
+}
+
+impl FilterState {
+    fn compile(include_patterns: &[String], exclude_patterns: &[String]) -> Result<Self, String> {
+        Ok(FilterState {
+            include: Mutex::new(build_glob_set(include_patterns)?),
+            exclude: Mutex::new(build_glob_set(exclude_patterns)?),
+        })
+    }
+
+    /// Recompile and swap in a new set of patterns, taking effect for the
+    /// next event processed.
+    fn set(&self, include_patterns: &[String], exclude_patterns: &[String]) -> Result<(), String> {
+        let include = build_glob_set(include_patterns)?;
+        let exclude = build_glob_set(exclude_patterns)?;
+        if let Ok(mut slot) = self.include.lock() {
+            *slot = include;
+        }
+        if let Ok(mut slot) = self.exclude.lock() {
+            *slot = exclude;
+        }
+        Ok(())
+    }
+
+    /// True if `path` should be processed: not matched by `exclude_patterns`,
+    /// and matched by `include_patterns` whenever an include list is
+    /// configured. An empty filter (the default) always passes.
+    fn passes(&self, path: &Path) -> bool {
+        if let Ok(exclude) = self.exclude.lock()
+            && let Some(set) = exclude.as_ref()
+            && set.is_match(path)
+        {
+            return false;
+        }
+        if let Ok(include) = self.include.lock()
+            && let Some(set) = include.as_ref()
+        {
+            return set.is_match(path);
+        }
+        true
+    }
+}
+
+/// Compile `patterns` into a `GlobSet`, or `None` if `patterns` is empty.
+fn build_glob_set(patterns: &[String]) -> Result<Option<GlobSet>, String> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| format!("Invalid filter pattern {:?}: {}", pattern, e))?;
+        builder.add(glob);
+    }
+    builder.build().map(Some).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// RENAME DETECTION
+// ============================================================================
+
+/// Enough of a file's identity to recognize it across a Remove+Create pair:
+/// size, plus inode where the platform exposes one (decisive when present,
+/// since two different files are vanishingly unlikely to share an inode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileFingerprint {
+    size: u64,
+    inode: Option<u64>,
+}
+
+impl FileFingerprint {
+    fn from_metadata(metadata: &std::fs::Metadata) -> Self {
+        FileFingerprint {
+            size: metadata.len(),
+            inode: file_inode(metadata),
+        }
+    }
+
+    /// True if `self` and `other` plausibly describe the same underlying
+    /// file. Inode equality wins when both sides have one; otherwise fall
+    /// back to a size match.
+    fn plausibly_same(&self, other: &FileFingerprint) -> bool {
+        match (self.inode, other.inode) {
+            (Some(a), Some(b)) => a == b,
+            _ => self.size == other.size,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn file_inode(metadata: &std::fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_inode(_metadata: &std::fs::Metadata) -> Option<u64> {
+    None
+}
+
+/// A Remove event buffered with the fingerprint it had the last time we saw
+/// it (the path is already gone by the time Remove fires, so this is the
+/// only identity we have left to match against a later Create).
+type PendingRemove = (FileFingerprint, PathBuf, Instant);
+
+/// Drop buffered removes that have aged out of the rename-pairing window.
+fn prune_pending_removes(pending: &mut Vec<PendingRemove>, window: Duration) {
+    let now = Instant::now();
+    pending.retain(|(_, _, removed_at)| now.duration_since(*removed_at) < window);
+}
+
+/// Buffers a raw Created/Modified event per path, coalescing a burst of
+/// edits (e.g. an editor's several saves while formatting-on-save runs)
+/// into one logical change. A later event for the same path resets the
+/// window rather than producing a second event; `drain_ready` hands back
+/// whichever paths have gone quiet for at least `debounce_ms`. Renamed
+/// events bypass this queue entirely and are still reported immediately.
+#[derive(Default)]
+struct CoalesceQueue {
+    pending: Mutex<HashMap<PathBuf, (FileEvent, Instant)>>,
+}
+
+impl CoalesceQueue {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `event`, resetting its window. A `Created` already queued for
+    /// this path stays `Created` even if a `Modified` arrives next — the
+    /// file is still new from the caller's point of view.
+    fn upsert(&self, event: FileEvent) {
+        let Ok(mut pending) = self.pending.lock() else { return };
+        let path = PathBuf::from(&event.path);
+        let merged = match pending.get(&path) {
+            Some((existing, _)) if existing.event_type == FileEventType::Created => existing.clone(),
+            _ => event,
+        };
+        pending.insert(path, (merged, Instant::now()));
+    }
+
+    /// Remove and return every queued event whose window has elapsed.
+    fn drain_ready(&self, window: Duration) -> Vec<FileEvent> {
+        let Ok(mut pending) = self.pending.lock() else { return Vec::new() };
+        let now = Instant::now();
+        let ready: Vec<PathBuf> = pending
+            .iter()
+            .filter(|(_, (_, last_seen))| now.duration_since(*last_seen) >= window)
+            .map(|(path, _)| path.clone())
+            .collect();
+        ready.into_iter().filter_map(|path| pending.remove(&path).map(|(event, _)| event)).collect()
+    }
+}
+
+/// The mutable state `process_event` needs across calls to coalesce,
+/// filter, and pair up renames. Bundled into one struct so it can be
+/// threaded through `run_watcher`/`process_event` as a single argument.
+struct DedupState {
+    coalesce: Arc<CoalesceQueue>,
+    ignore_engine: Arc<IgnoreEngine>,
+    filters: Arc<FilterState>,
+    known_files: Arc<Mutex<HashMap<PathBuf, FileFingerprint>>>,
+    pending_removes: Arc<Mutex<Vec<PendingRemove>>>,
+}
+
+// ============================================================================
+// WATCHER BACKEND
+// ============================================================================
+
+/// Common interface for a directory-watching backend. Implementors deliver
+/// events through whatever `Sender<Result<Event, notify::Error>>` they were
+/// constructed with, so the event loop in `run_watcher` doesn't need to know
+/// which backend produced a given event.
+trait DirWatcher: Send + Sync {
+    fn add(&self, path: &Path) -> Result<(), String>;
+    fn remove(&self, path: &Path) -> Result<(), String>;
+}
+
+/// Wraps the OS-native `RecommendedWatcher` (inotify/FSEvents/
+/// ReadDirectoryChangesW).
+struct NativeDirWatcher {
+    inner: Mutex<RecommendedWatcher>,
+}
+
+impl NativeDirWatcher {
+    fn new(tx: Sender<Result<Event, notify::Error>>) -> Result<Self, String> {
+        let watcher = RecommendedWatcher::new(tx, Config::default())
+            .map_err(|e| format!("Failed to create watcher: {}", e))?;
+        Ok(NativeDirWatcher { inner: Mutex::new(watcher) })
+    }
+}
+
+impl DirWatcher for NativeDirWatcher {
+    fn add(&self, path: &Path) -> Result<(), String> {
+        let mut watcher = self.inner.lock().map_err(|e| format!("Lock error: {}", e))?;
+        watcher.watch(path, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", path.display(), e))
+    }
+
+    fn remove(&self, path: &Path) -> Result<(), String> {
+        let mut watcher = self.inner.lock().map_err(|e| format!("Lock error: {}", e))?;
+        watcher.unwatch(path)
+            .map_err(|e| format!("Failed to unwatch {}: {}", path.display(), e))
+    }
+}
+
+type PollSnapshot = HashMap<PathBuf, (SystemTime, u64)>;
+
+/// Polling fallback for filesystems where native watching silently produces
+/// nothing (network shares, some WSL/virtualized mounts): stats the tree on
+/// a fixed interval and diffs mtime/size snapshots to synthesize Create/
+/// Modify events.
+struct PollDirWatcher {
+    paths: Arc<Mutex<Vec<PathBuf>>>,
+    stop: Arc<Mutex<bool>>,
+}
+
+impl PollDirWatcher {
+    fn new(tx: Sender<Result<Event, notify::Error>>, poll_interval: Duration) -> Self {
+        let paths: Arc<Mutex<Vec<PathBuf>>> = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(Mutex::new(false));
+
+        let thread_paths = Arc::clone(&paths);
+        let thread_stop = Arc::clone(&stop);
+        thread::spawn(move || poll_loop(tx, thread_paths, thread_stop, poll_interval));
+
+        PollDirWatcher { paths, stop }
+    }
+}
+
+impl DirWatcher for PollDirWatcher {
+    fn add(&self, path: &Path) -> Result<(), String> {
+        let mut paths = self.paths.lock().map_err(|e| format!("Lock error: {}", e))?;
+        if !paths.iter().any(|p| p == path) {
+            paths.push(path.to_path_buf());
+        }
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> Result<(), String> {
+        let mut paths = self.paths.lock().map_err(|e| format!("Lock error: {}", e))?;
+        paths.retain(|p| p != path);
+        Ok(())
+    }
+}
+
+impl Drop for PollDirWatcher {
+    fn drop(&mut self) {
+        if let Ok(mut stop) = self.stop.lock() {
+            *stop = true;
+        }
+    }
+}
+
+fn poll_loop(
+    tx: Sender<Result<Event, notify::Error>>,
+    paths: Arc<Mutex<Vec<PathBuf>>>,
+    stop: Arc<Mutex<bool>>,
+    poll_interval: Duration,
+) {
+    let mut snapshot: PollSnapshot = HashMap::new();
+
+    loop {
+        if let Ok(s) = stop.lock()
+            && *s
+        {
+            break;
+        }
+
+        let roots = paths.lock().map(|p| p.clone()).unwrap_or_default();
+        let mut current: PollSnapshot = HashMap::new();
+        for root in &roots {
+            walk_for_poll(root, &mut current);
+        }
+
+        for (path, stat) in &current {
+            let event_kind = match snapshot.get(path) {
+                None => Some(EventKind::Create(notify::event::CreateKind::File)),
+                Some(prev_stat) if prev_stat != stat => {
+                    Some(EventKind::Modify(notify::event::ModifyKind::Data(notify::event::DataChange::Any)))
+                }
+                Some(_) => None,
+            };
+
+            if let Some(kind) = event_kind {
+                let _ = tx.send(Ok(Event::new(kind).add_path(path.clone())));
+            }
+        }
+
+        snapshot = current;
+        thread::sleep(poll_interval);
+    }
+}
+
+/// Walk `root` recording each file's (mtime, size) into `out`.
+fn walk_for_poll(root: &Path, out: &mut PollSnapshot) {
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+
+            if let Ok(metadata) = entry.metadata() {
+                let mtime = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                out.insert(path, (mtime, metadata.len()));
+            }
+        }
+    }
+}
+
+/// The backend(s) selected for a running watcher, built once up front so
+/// both the event loop and a live `WatchHandle` can register/unregister
+/// paths against the same instances while watching continues.
+struct BackendSet {
+    native: Option<NativeDirWatcher>,
+    poll: Option<PollDirWatcher>,
+}
+
+impl BackendSet {
+    fn new(backend_mode: WatchBackend, tx: Sender<Result<Event, notify::Error>>, poll_interval: Duration) -> Self {
+        let native = match backend_mode {
+            WatchBackend::Poll => None,
+            _ => match NativeDirWatcher::new(tx.clone()) {
+                Ok(watcher) => Some(watcher),
+                Err(e) => {
+                    eprintln!("[FILE_WATCHER] Native backend unavailable: {}", e);
+                    None
+                }
+            },
+        };
+        let poll = match backend_mode {
+            WatchBackend::Native => None,
+            _ => Some(PollDirWatcher::new(tx, poll_interval)),
+        };
+        BackendSet { native, poll }
+    }
+}
+
+/// Register `path` with whichever backend accepts it for `backend_mode`,
+/// falling back from native to polling in `Auto` mode when native
+/// registration errors. Returns which backend ended up watching the path,
+/// for logging, or `None` if neither backend is available.
+fn register_path(path: &Path, backend_mode: WatchBackend, backends: &BackendSet) -> Option<&'static str> {
+    let native_registered = backend_mode != WatchBackend::Poll
+        && backends.native.as_ref().is_some_and(|watcher| match watcher.add(path) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("[FILE_WATCHER] Native watch failed for {}: {}", path.display(), e);
+                false
+            }
+        });
+
+    if native_registered {
+        Some("native")
+    } else if let Some(poll) = &backends.poll {
+        let _ = poll.add(path);
+        Some("poll")
+    } else {
+        None
+    }
+}
+
+/// Unregister `path` from whichever backend(s) are currently watching it.
+/// A no-op on a backend that was never watching the path.
+fn unregister_path(path: &Path, backends: &BackendSet) {
+    if let Some(native) = &backends.native {
+        let _ = native.remove(path);
+    }
+    if let Some(poll) = &backends.poll {
+        let _ = poll.remove(path);
+    }
+}
+
 // ============================================================================
 // FILE WATCHER
 // ============================================================================
@@ -87,47 +607,112 @@ impl Default for FileWatcherState {
 pub struct FileWatcher {
     config: WatchConfig,
     state: Arc<Mutex<FileWatcherState>>,
-    debounce_map: Arc<Mutex<HashMap<PathBuf, Instant>>>,
+    coalesce: Arc<CoalesceQueue>,
     event_sender: Option<Sender<FileEvent>>,
+    ignore_engine: Arc<IgnoreEngine>,
+    filters: Arc<FilterState>,
+    known_files: Arc<Mutex<HashMap<PathBuf, FileFingerprint>>>,
+    pending_removes: Arc<Mutex<Vec<PendingRemove>>>,
+    action_config: Option<crate::watch_actions::ActionConfig>,
 }
 
 impl FileWatcher {
     pub fn new(config: WatchConfig) -> Self {
+        let filters = FilterState::compile(&config.include_patterns, &config.exclude_patterns)
+            .unwrap_or_else(|e| {
+                eprintln!("[FILE_WATCHER] {}; watching without pattern filters", e);
+                FilterState::compile(&[], &[]).expect("empty patterns always compile")
+            });
+
         FileWatcher {
             config,
             state: Arc::new(Mutex::new(FileWatcherState::default())),
-            debounce_map: Arc::new(Mutex::new(HashMap::new())),
+            coalesce: Arc::new(CoalesceQueue::new()),
             event_sender: None,
+            ignore_engine: Arc::new(IgnoreEngine::new()),
+            filters: Arc::new(filters),
+            known_files: Arc::new(Mutex::new(HashMap::new())),
+            pending_removes: Arc::new(Mutex::new(Vec::new())),
+            action_config: None,
         }
     }
-    
-    /// Start watching the configured paths
-    pub fn start(&mut self) -> Result<Receiver<FileEvent>, String> {
+
+    /// Build a watcher that additionally runs `action_config`'s configured
+    /// action (re-index or external command) once a batch of events settles.
+    pub fn with_action(config: WatchConfig, action_config: crate::watch_actions::ActionConfig) -> Self {
+        FileWatcher {
+            action_config: Some(action_config),
+            ..FileWatcher::new(config)
+        }
+    }
+
+    /// Start watching the configured paths. Returns the event stream
+    /// alongside a `WatchHandle` that can add or remove watched paths while
+    /// the watcher thread keeps running.
+    pub fn start(&mut self) -> Result<(Receiver<FileEvent>, WatchHandle), String> {
         // Create channel for events
         let (tx, rx) = channel::<FileEvent>();
         self.event_sender = Some(tx.clone());
-        
+
         // Update state
         {
             let mut state = self.state.lock().map_err(|e| format!("Lock error: {}", e))?;
             state.is_running = true;
             state.watched_paths = self.config.paths.clone();
         }
-        
+
+        // Walk each watched root up front so the first events are already
+        // evaluated against real ignore rules instead of an empty cache.
+        for path_str in &self.config.paths {
+            self.ignore_engine.build_root(Path::new(path_str));
+        }
+
+        // Build the backend(s) up front (rather than inside the watcher
+        // thread) so the returned `WatchHandle` can register/unregister
+        // paths against the very same instances while watching continues.
+        let (notify_tx, notify_rx) = channel::<Result<Event, notify::Error>>();
+        let backends = Arc::new(BackendSet::new(self.config.backend, notify_tx, Duration::from_millis(self.config.poll_interval_ms)));
+
+        // If an action runner is configured, give it its own copy of each
+        // event on a separate thread, alongside the channel returned to the
+        // caller.
+        let action_tx = self.action_config.clone().map(|action_config| {
+            let (action_tx, action_rx) = channel::<FileEvent>();
+            thread::spawn(move || {
+                crate::watch_actions::ActionRunner::new(action_config).run(action_rx);
+            });
+            action_tx
+        });
+
         // Clone what we need for the thread
         let config = self.config.clone();
         let state = Arc::clone(&self.state);
-        let debounce_map = Arc::clone(&self.debounce_map);
-        
+        let dedup = DedupState {
+            coalesce: Arc::clone(&self.coalesce),
+            ignore_engine: Arc::clone(&self.ignore_engine),
+            filters: Arc::clone(&self.filters),
+            known_files: Arc::clone(&self.known_files),
+            pending_removes: Arc::clone(&self.pending_removes),
+        };
+        let thread_backends = Arc::clone(&backends);
+
         // Spawn watcher thread
         thread::spawn(move || {
-            if let Err(e) = run_watcher(config, tx, state, debounce_map) {
+            let sinks = EventSinks { event_tx: tx, action_tx };
+            if let Err(e) = run_watcher(config, sinks, state, dedup, thread_backends, notify_rx) {
                 eprintln!("[FILE_WATCHER] Error: {}", e);
             }
         });
-        
+
         println!("[FILE_WATCHER] Started watching {} paths", self.config.paths.len());
-        Ok(rx)
+
+        let handle = WatchHandle {
+            state: Arc::clone(&self.state),
+            backends,
+            backend_mode: self.config.backend,
+            filters: Arc::clone(&self.filters),
+        };
+        Ok((rx, handle))
     }
     
     /// Stop watching
@@ -157,32 +742,92 @@ impl FileWatcher {
     }
 }
 
+/// A live handle to a running `FileWatcher`, returned alongside the event
+/// stream from `start()`. Lets the caller register or drop watched paths
+/// (e.g. when the user picks a new folder in the UI) without tearing down
+/// and restarting the whole watcher.
+pub struct WatchHandle {
+    state: Arc<Mutex<FileWatcherState>>,
+    backends: Arc<BackendSet>,
+    backend_mode: WatchBackend,
+    filters: Arc<FilterState>,
+}
+
+impl WatchHandle {
+    /// Start watching `path`, falling back from native to polling in `Auto`
+    /// mode the same way the initial paths are registered.
+    pub fn add_path(&self, path: &Path) -> Result<(), String> {
+        match register_path(path, self.backend_mode, &self.backends) {
+            Some(_) => {
+                let path_str = path.to_string_lossy().to_string();
+                let mut state = self.state.lock().map_err(|e| format!("Lock error: {}", e))?;
+                if !state.watched_paths.contains(&path_str) {
+                    state.watched_paths.push(path_str);
+                }
+                Ok(())
+            }
+            None => Err(format!("No backend available to watch: {}", path.display())),
+        }
+    }
+
+    /// Stop watching `path`. A no-op if it wasn't being watched.
+    pub fn remove_path(&self, path: &Path) -> Result<(), String> {
+        unregister_path(path, &self.backends);
+        let path_str = path.to_string_lossy().to_string();
+        let mut state = self.state.lock().map_err(|e| format!("Lock error: {}", e))?;
+        state.watched_paths.retain(|p| p != &path_str);
+        Ok(())
+    }
+
+    /// The paths currently being watched.
+    pub fn list_paths(&self) -> Vec<String> {
+        self.state.lock().map(|s| s.watched_paths.clone()).unwrap_or_default()
+    }
+
+    /// Recompile and swap in a new set of glob include/exclude filters,
+    /// taking effect for the next event the watcher processes.
+    pub fn set_filters(&self, include_patterns: &[String], exclude_patterns: &[String]) -> Result<(), String> {
+        self.filters.set(include_patterns, exclude_patterns)
+    }
+}
+
+/// Where produced `FileEvent`s get sent: the channel handed back to
+/// `FileWatcher::start()`'s caller, plus an optional second copy for an
+/// attached `watch_actions::ActionRunner`.
+struct EventSinks {
+    event_tx: Sender<FileEvent>,
+    action_tx: Option<Sender<FileEvent>>,
+}
+
 /// The actual watcher loop running in a thread
 fn run_watcher(
     config: WatchConfig,
-    event_tx: Sender<FileEvent>,
+    sinks: EventSinks,
     state: Arc<Mutex<FileWatcherState>>,
-    debounce_map: Arc<Mutex<HashMap<PathBuf, Instant>>>,
+    dedup: DedupState,
+    backends: Arc<BackendSet>,
+    rx: Receiver<Result<Event, notify::Error>>,
 ) -> Result<(), String> {
-    let (tx, rx) = channel::<Result<Event, notify::Error>>();
-    
-    let mut watcher = RecommendedWatcher::new(tx, Config::default())
-        .map_err(|e| format!("Failed to create watcher: {}", e))?;
-    
-    // Add paths to watch
+    let EventSinks { event_tx, action_tx } = sinks;
+
+    // Register each watched path with the configured backend. In `Auto`, try
+    // native first and transparently fall back to polling for any path whose
+    // native registration errors.
     for path_str in &config.paths {
         let path = Path::new(path_str);
-        if path.exists() {
-            watcher.watch(path, RecursiveMode::Recursive)
-                .map_err(|e| format!("Failed to watch {}: {}", path_str, e))?;
-            println!("[FILE_WATCHER] Watching: {}", path_str);
-        } else {
+        if !path.exists() {
             println!("[FILE_WATCHER] Path does not exist, skipping: {}", path_str);
+            continue;
+        }
+
+        match register_path(path, config.backend, &backends) {
+            Some(kind) => println!("[FILE_WATCHER] Watching ({}): {}", kind, path_str),
+            None => eprintln!("[FILE_WATCHER] No backend available to watch: {}", path_str),
         }
     }
-    
+
     let debounce_duration = Duration::from_millis(config.debounce_ms);
-    
+
     // Event loop
     loop {
         // Check if we should stop
@@ -193,27 +838,16 @@ fn run_watcher(
                 }
             }
         }
-        
+
+        let mut channel_closed = false;
+
         // Non-blocking receive with timeout
         match rx.recv_timeout(Duration::from_millis(100)) {
             Ok(Ok(event)) => {
-                if let Some(file_event) = process_event(&event, &config, &debounce_map, debounce_duration) {
-                    // Send event
-                    if event_tx.send(file_event.clone()).is_err() {
-                        // Channel closed, stop watching
-                        break;
-                    }
-                    
-                    // Store in state for polling
-                    if let Ok(mut s) = state.lock() {
-                        s.pending_events.push(file_event);
-                        s.event_count += 1;
-                        
-                        // Keep only last 50 events
-                        if s.pending_events.len() > 50 {
-                            s.pending_events.remove(0);
-                        }
-                    }
+                if let Some(file_event) = process_event(&event, &config, &dedup)
+                    && !emit_event(file_event, &event_tx, &action_tx, &state)
+                {
+                    channel_closed = true;
                 }
             }
             Ok(Err(e)) => {
@@ -223,34 +857,97 @@ fn run_watcher(
                 // Timeout, continue loop
             }
         }
+
+        // Every tick, flush any coalesced Created/Modified events whose
+        // debounce window has elapsed since their last update.
+        for file_event in dedup.coalesce.drain_ready(debounce_duration) {
+            if !emit_event(file_event, &event_tx, &action_tx, &state) {
+                channel_closed = true;
+            }
+        }
+
+        if channel_closed {
+            break;
+        }
     }
-    
+
     println!("[FILE_WATCHER] Watcher thread exiting");
     Ok(())
 }
 
+/// Send a ready `FileEvent` to the caller's channel (and the attached action
+/// runner, if any), and append it to in-memory state for `get_watcher_status`
+/// and live polling. Returns `false` if the caller's channel has been
+/// dropped, signaling the watcher thread to stop.
+fn emit_event(
+    file_event: FileEvent,
+    event_tx: &Sender<FileEvent>,
+    action_tx: &Option<Sender<FileEvent>>,
+    state: &Arc<Mutex<FileWatcherState>>,
+) -> bool {
+    if event_tx.send(file_event.clone()).is_err() {
+        return false;
+    }
+
+    if let Some(action_tx) = action_tx {
+        let _ = action_tx.send(file_event.clone());
+    }
+
+    if let Ok(mut s) = state.lock() {
+        s.pending_events.push(file_event);
+        s.event_count += 1;
+
+        // Keep only last 50 events
+        if s.pending_events.len() > 50 {
+            s.pending_events.remove(0);
+        }
+    }
+
+    true
+}
+
 /// Process a raw notify event into our FileEvent
 fn process_event(
     event: &Event,
     config: &WatchConfig,
-    debounce_map: &Arc<Mutex<HashMap<PathBuf, Instant>>>,
-    debounce_duration: Duration,
+    dedup: &DedupState,
 ) -> Option<FileEvent> {
+    let rename_window = Duration::from_millis(config.rename_window_ms);
     let path = event.paths.first()?;
-    
+    let DedupState { coalesce, ignore_engine, filters, known_files, pending_removes } = dedup;
+
+    // An ignore file changing invalidates the cached rules for whichever
+    // watched root it lives under, rather than being reported as a regular
+    // file event.
+    if let Some(name) = path.file_name().and_then(|n| n.to_str())
+        && IGNORE_FILE_NAMES.contains(&name)
+    {
+        if let Some(root) = find_watch_root(path, &config.paths) {
+            ignore_engine.invalidate(&root);
+        }
+        return None;
+    }
+
     // Skip directories
     if path.is_dir() {
         return None;
     }
-    
-    // Check ignore patterns
-    let path_str = path.to_string_lossy().to_lowercase();
-    for pattern in &config.ignore_patterns {
-        if path_str.contains(&pattern.to_lowercase()) {
-            return None;
-        }
+
+    // Evaluate the gitignore/.ignore rule stack for whichever watched root
+    // this path falls under
+    if let Some(root) = find_watch_root(path, &config.paths)
+        && ignore_engine.is_ignored(&root, path, false)
+    {
+        return None;
     }
-    
+
+    // Apply the configured glob include/exclude filters on top of the
+    // ignore engine (e.g. editor swap files, build output the user doesn't
+    // want re-indexed even though it isn't in a `.gitignore`).
+    if !filters.passes(path) {
+        return None;
+    }
+
     // Get file extension and type
     let ext = path.extension()
         .map(|e| e.to_string_lossy().to_lowercase())
@@ -261,42 +958,80 @@ fn process_event(
     if config.watch_only_organizable && !doc_type.is_organizable() {
         return None;
     }
-    
-    // Debouncing: skip if we just saw this file
-    {
-        let mut map = debounce_map.lock().ok()?;
-        let now = Instant::now();
-        
-        if let Some(last_time) = map.get(path) {
-            if now.duration_since(*last_time) < debounce_duration {
-                return None; // Too soon, skip
-            }
+
+    // A Remove doesn't produce a FileEvent on its own; buffer the fingerprint
+    // it had the last time we saw it so a Create arriving within
+    // `rename_window` can be recognized as the other half of a rename/move.
+    if matches!(event.kind, EventKind::Remove(_)) {
+        if let Ok(mut known) = known_files.lock()
+            && let Some(fingerprint) = known.remove(path)
+            && let Ok(mut pending) = pending_removes.lock()
+        {
+            prune_pending_removes(&mut pending, rename_window);
+            pending.push((fingerprint, path.clone(), Instant::now()));
         }
-        
-        map.insert(path.clone(), now);
+        return None;
     }
-    
+
     // Determine event type
     let event_type = match &event.kind {
-        EventKind::Create(_) => FileEventType::Created,
-        EventKind::Modify(_) => FileEventType::Modified,
+        EventKind::Create(_) => {
+            // Pair against a buffered Remove with a matching fingerprint
+            // before falling back to a plain Created.
+            let renamed_from = std::fs::metadata(path).ok().and_then(|metadata| {
+                let fingerprint = FileFingerprint::from_metadata(&metadata);
+                if let Ok(mut known) = known_files.lock() {
+                    known.insert(path.clone(), fingerprint);
+                }
+
+                let mut pending = pending_removes.lock().ok()?;
+                prune_pending_removes(&mut pending, rename_window);
+                let position = pending.iter().position(|(fp, _, _)| fp.plausibly_same(&fingerprint))?;
+                Some(pending.remove(position).1)
+            });
+
+            match renamed_from {
+                Some(from) => FileEventType::Renamed { from: from.to_string_lossy().to_string() },
+                None => FileEventType::Created,
+            }
+        }
+        EventKind::Modify(_) => {
+            if let Ok(metadata) = std::fs::metadata(path)
+                && let Ok(mut known) = known_files.lock()
+            {
+                known.insert(path.clone(), FileFingerprint::from_metadata(&metadata));
+            }
+            FileEventType::Modified
+        }
         EventKind::Any => FileEventType::Modified,
-        _ => return None, // Ignore removes, access, etc.
+        _ => return None, // Ignore other kinds (access, etc.)
     };
-    
+
     let file_name = path.file_name()
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_default();
     
     let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-    
-    Some(FileEvent {
+
+    let file_event = FileEvent {
         path: path.to_string_lossy().to_string(),
         file_name,
         event_type,
         doc_type,
         timestamp,
-    })
+    };
+
+    // A rename is already a single, fully-formed logical change (it only
+    // exists once the Remove+Create pairing above resolves), so it's
+    // reported immediately. Plain Created/Modified events go through the
+    // coalescing queue instead, so a burst of saves to the same path
+    // collapses into one event once things go quiet.
+    if matches!(file_event.event_type, FileEventType::Renamed { .. }) {
+        return Some(file_event);
+    }
+
+    coalesce.upsert(file_event);
+    None
 }
 
 // ============================================================================
@@ -433,4 +1168,255 @@ mod tests {
         assert_eq!(config.debounce_ms, 2000);
         assert!(config.watch_only_organizable);
     }
+
+    fn unique_temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "wayfinder_watcher_test_{}_{}_{}",
+            name,
+            std::process::id(),
+            Instant::now().elapsed().as_nanos()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_builtin_rules_ignore_without_any_ignore_file() {
+        let root = unique_temp_dir("builtin");
+        std::fs::create_dir_all(root.join("node_modules")).unwrap();
+
+        let engine = IgnoreEngine::new();
+        engine.build_root(&root);
+
+        assert!(engine.is_ignored(&root, &root.join("node_modules"), true));
+        assert!(!engine.is_ignored(&root, &root.join("notes.md"), false));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_gitignore_file_rules_and_negation() {
+        let root = unique_temp_dir("gitignore");
+        std::fs::write(root.join(".gitignore"), "*.log\n!keep.log\n").unwrap();
+
+        let engine = IgnoreEngine::new();
+        engine.build_root(&root);
+
+        assert!(engine.is_ignored(&root, &root.join("debug.log"), false));
+        assert!(!engine.is_ignored(&root, &root.join("keep.log"), false));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_nested_gitignore_overrides_parent() {
+        let root = unique_temp_dir("nested");
+        std::fs::write(root.join(".gitignore"), "*.draft\n").unwrap();
+        let sub = root.join("published");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::write(sub.join(".gitignore"), "!final.draft\n").unwrap();
+
+        let engine = IgnoreEngine::new();
+        engine.build_root(&root);
+
+        assert!(engine.is_ignored(&root, &root.join("idea.draft"), false));
+        assert!(!engine.is_ignored(&root, &sub.join("final.draft"), false));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_find_watch_root_matches_ancestor() {
+        let roots = vec!["/home/user/Downloads".to_string(), "/home/user/Desktop".to_string()];
+        let found = find_watch_root(Path::new("/home/user/Downloads/resume.pdf"), &roots);
+        assert_eq!(found, Some(PathBuf::from("/home/user/Downloads")));
+
+        assert_eq!(find_watch_root(Path::new("/etc/passwd"), &roots), None);
+    }
+
+    #[test]
+    fn test_poll_watcher_detects_create_and_modify() {
+        let root = unique_temp_dir("poll");
+        let (tx, rx) = channel::<Result<Event, notify::Error>>();
+        let poll = PollDirWatcher::new(tx, Duration::from_millis(20));
+        poll.add(&root).unwrap();
+
+        // First poll tick just establishes the baseline snapshot (empty dir).
+        thread::sleep(Duration::from_millis(60));
+        while rx.try_recv().is_ok() {}
+
+        std::fs::write(root.join("new.txt"), "hello").unwrap();
+        let created = rx.recv_timeout(Duration::from_secs(2)).expect("expected a create event").unwrap();
+        assert!(matches!(created.kind, EventKind::Create(_)));
+        assert_eq!(created.paths.first(), Some(&root.join("new.txt")));
+
+        std::fs::write(root.join("new.txt"), "hello world").unwrap();
+        let modified = rx.recv_timeout(Duration::from_secs(2)).expect("expected a modify event").unwrap();
+        assert!(matches!(modified.kind, EventKind::Modify(_)));
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_rename_pairing_coalesces_remove_and_create() {
+        let root = unique_temp_dir("rename");
+        let old_path = root.join("old.txt");
+        let new_path = root.join("new.txt");
+        std::fs::write(&old_path, "same content").unwrap();
+
+        let config = WatchConfig {
+            paths: vec![root.to_string_lossy().to_string()],
+            watch_only_organizable: false,
+            ..Default::default()
+        };
+
+        let dedup = DedupState {
+            coalesce: Arc::new(CoalesceQueue::new()),
+            ignore_engine: Arc::new(IgnoreEngine::new()),
+            filters: Arc::new(FilterState::compile(&[], &[]).unwrap()),
+            known_files: Arc::new(Mutex::new(HashMap::new())),
+            pending_removes: Arc::new(Mutex::new(Vec::new())),
+        };
+        dedup.ignore_engine.build_root(&root);
+
+        // Register old.txt's fingerprint as if we'd seen a Create for it.
+        let seen = Event::new(EventKind::Create(notify::event::CreateKind::File)).add_path(old_path.clone());
+        process_event(&seen, &config, &dedup);
+
+        std::fs::rename(&old_path, &new_path).unwrap();
+        let removed = Event::new(EventKind::Remove(notify::event::RemoveKind::File)).add_path(old_path.clone());
+        assert!(process_event(&removed, &config, &dedup).is_none());
+        assert_eq!(dedup.pending_removes.lock().unwrap().len(), 1);
+
+        let created = Event::new(EventKind::Create(notify::event::CreateKind::File)).add_path(new_path.clone());
+        let file_event = process_event(&created, &config, &dedup)
+            .expect("expected a coalesced rename event");
+
+        assert_eq!(file_event.event_type, FileEventType::Renamed { from: old_path.to_string_lossy().to_string() });
+        assert!(dedup.pending_removes.lock().unwrap().is_empty());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_unmatched_remove_ages_out_of_rename_window() {
+        let root = unique_temp_dir("rename_stale");
+        let old_path = root.join("old.txt");
+        std::fs::write(&old_path, "data").unwrap();
+
+        let config = WatchConfig {
+            paths: vec![root.to_string_lossy().to_string()],
+            watch_only_organizable: false,
+            rename_window_ms: 10,
+            ..Default::default()
+        };
+
+        let dedup = DedupState {
+            coalesce: Arc::new(CoalesceQueue::new()),
+            ignore_engine: Arc::new(IgnoreEngine::new()),
+            filters: Arc::new(FilterState::compile(&[], &[]).unwrap()),
+            known_files: Arc::new(Mutex::new(HashMap::new())),
+            pending_removes: Arc::new(Mutex::new(Vec::new())),
+        };
+        dedup.ignore_engine.build_root(&root);
+
+        let seen = Event::new(EventKind::Create(notify::event::CreateKind::File)).add_path(old_path.clone());
+        process_event(&seen, &config, &dedup);
+        // Flush the coalesced registration event, as `run_watcher`'s
+        // periodic drain would have by the time the rename window matters.
+        dedup.coalesce.drain_ready(Duration::from_millis(0));
+
+        std::fs::remove_file(&old_path).unwrap();
+        let removed = Event::new(EventKind::Remove(notify::event::RemoveKind::File)).add_path(old_path.clone());
+        process_event(&removed, &config, &dedup);
+        assert_eq!(dedup.pending_removes.lock().unwrap().len(), 1);
+
+        thread::sleep(Duration::from_millis(30));
+
+        let new_path = root.join("new.txt");
+        std::fs::write(&new_path, "data").unwrap();
+        let created = Event::new(EventKind::Create(notify::event::CreateKind::File)).add_path(new_path.clone());
+        // An unmatched Create is a plain Created event, which goes through
+        // the coalescing queue rather than being returned immediately.
+        assert!(process_event(&created, &config, &dedup).is_none());
+
+        let ready = dedup.coalesce.drain_ready(Duration::from_millis(0));
+        assert_eq!(ready.len(), 1);
+        assert_eq!(ready[0].event_type, FileEventType::Created);
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_watch_handle_add_and_remove_path_updates_watched_paths() {
+        let root = unique_temp_dir("handle_add");
+        let extra = unique_temp_dir("handle_extra");
+
+        let config = WatchConfig {
+            paths: vec![root.to_string_lossy().to_string()],
+            backend: WatchBackend::Poll,
+            ..Default::default()
+        };
+
+        let mut watcher = FileWatcher::new(config);
+        let (_rx, handle) = watcher.start().expect("watcher should start");
+
+        assert_eq!(handle.list_paths(), vec![root.to_string_lossy().to_string()]);
+
+        handle.add_path(&extra).expect("adding a new path should succeed");
+        let mut watched = handle.list_paths();
+        watched.sort();
+        let mut expected = vec![root.to_string_lossy().to_string(), extra.to_string_lossy().to_string()];
+        expected.sort();
+        assert_eq!(watched, expected);
+
+        handle.remove_path(&root).expect("removing a watched path should succeed");
+        assert_eq!(handle.list_paths(), vec![extra.to_string_lossy().to_string()]);
+
+        watcher.stop().unwrap();
+        std::fs::remove_dir_all(&root).ok();
+        std::fs::remove_dir_all(&extra).ok();
+    }
+
+    #[test]
+    fn test_coalesce_queue_merges_rapid_updates_into_one_event() {
+        let queue = CoalesceQueue::new();
+        let make_event = |event_type: FileEventType| FileEvent {
+            path: "/tmp/doc.md".to_string(),
+            file_name: "doc.md".to_string(),
+            event_type,
+            doc_type: DocumentType::from_extension("md"),
+            timestamp: "2024-01-01 00:00:00".to_string(),
+        };
+
+        queue.upsert(make_event(FileEventType::Created));
+        queue.upsert(make_event(FileEventType::Modified));
+        queue.upsert(make_event(FileEventType::Modified));
+
+        // Nothing is ready yet: each upsert resets the window.
+        assert!(queue.drain_ready(Duration::from_secs(60)).is_empty());
+
+        let ready = queue.drain_ready(Duration::from_millis(0));
+        assert_eq!(ready.len(), 1);
+        // The sticky Created from the first upsert wins over the later Modified.
+        assert_eq!(ready[0].event_type, FileEventType::Created);
+    }
+
+    #[test]
+    fn test_filter_state_exclude_wins_over_include() {
+        let filters = FilterState::compile(
+            &["*.md".to_string()],
+            &["*.tmp".to_string()],
+        ).unwrap();
+
+        assert!(filters.passes(Path::new("notes.md")));
+        assert!(!filters.passes(Path::new("notes.tmp")));
+        assert!(!filters.passes(Path::new("notes.txt"))); // not in include list
+    }
+
+    #[test]
+    fn test_filter_state_empty_patterns_pass_everything() {
+        let filters = FilterState::compile(&[], &[]).unwrap();
+        assert!(filters.passes(Path::new("anything.xyz")));
+    }
 }
@@ -5,8 +5,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 use std::process::Command;
+use walkdir::WalkDir;
 
 use crate::commands::FileEntry;
+use crate::git_backend::{self, GitOperationState, StatusEntry};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GitStatus {
@@ -18,6 +20,7 @@ pub struct GitStatus {
     pub days_since_commit: i64,
     pub last_commit_message: Option<String>,
     pub last_commit_date: Option<String>,
+    pub hours_worked: f64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -27,6 +30,15 @@ pub struct DuplicateFile {
     pub content_hash: String,
 }
 
+/// A file with unresolved merge conflict markers, surfaced so GitClippy can
+/// walk the user through conflicts one file at a time instead of offering
+/// only the nuclear abort.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub has_conflict_markers: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct FileSuggestion {
     pub file_path: String,
@@ -40,6 +52,18 @@ pub struct CommitSuggestion {
     pub files: Vec<String>,
     pub suggested_message: String,
     pub category: String,
+    pub conventional_message: String,
+    pub semver_bump: SemverBump,
+}
+
+/// The semver-impact a set of changes implies, ordered so the highest bump
+/// wins when aggregating across several `CommitSuggestion`s.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SemverBump {
+    None,
+    Patch,
+    Minor,
+    Major,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -51,6 +75,9 @@ pub struct GitClippyReport {
     pub duplicates: Vec<DuplicateFile>,
     pub commit_suggestions: Vec<CommitSuggestion>,
     pub copy_pattern_files: Vec<FileSuggestion>,
+    pub suggested_next_version: Option<String>,
+    pub operation_state: Option<GitOperationState>,
+    pub conflicted_files: Vec<ConflictedFile>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -109,29 +136,28 @@ pub fn get_git_status(repo_path: &str) -> Result<GitStatus, String> {
             days_since_commit: 0,
             last_commit_message: None,
             last_commit_date: None,
+            hours_worked: 0.0,
         });
     }
 
+    let backend = git_backend::default_backend();
+
     // Get current branch
-    let branch = run_git_command(repo_path, &["branch", "--show-current"])
-        .unwrap_or_else(|_| "unknown".to_string())
-        .trim()
-        .to_string();
+    let branch = backend.current_branch(repo_path).unwrap_or_else(|_| "unknown".to_string());
 
-    // Get status --porcelain for file counts
-    let status_output = run_git_command(repo_path, &["status", "--porcelain"]).unwrap_or_default();
+    // Get status entries for file counts
+    let status_entries = backend.status_entries(repo_path).unwrap_or_default();
 
     let mut staged = 0;
     let mut uncommitted = 0;
     let mut untracked = 0;
 
-    for line in status_output.lines() {
-        if line.len() < 2 {
-            continue;
-        }
-        let status_chars: Vec<char> = line.chars().take(2).collect();
+    for entry in &status_entries {
+        let mut status_chars = entry.status_code.chars();
+        let first = status_chars.next();
+        let second = status_chars.next();
 
-        match (status_chars.get(0), status_chars.get(1)) {
+        match (first, second) {
             (Some('?'), Some('?')) => untracked += 1,
             (Some(' '), Some(_)) => uncommitted += 1,
             (Some(_), Some(' ')) => staged += 1,
@@ -144,13 +170,9 @@ pub fn get_git_status(repo_path: &str) -> Result<GitStatus, String> {
     }
 
     // Get last commit info
-    let last_commit_message = run_git_command(repo_path, &["log", "-1", "--format=%s"])
-        .ok()
-        .map(|s| s.trim().to_string());
-
-    let last_commit_date = run_git_command(repo_path, &["log", "-1", "--format=%ci"])
-        .ok()
-        .map(|s| s.trim().to_string());
+    let last_commit = backend.last_commit(repo_path).ok().flatten();
+    let last_commit_message = last_commit.as_ref().map(|c| c.message.clone());
+    let last_commit_date = last_commit.as_ref().map(|c| c.date.clone());
 
     // Calculate days since last commit
     let days_since_commit = if let Some(ref date_str) = last_commit_date {
@@ -168,6 +190,9 @@ pub fn get_git_status(repo_path: &str) -> Result<GitStatus, String> {
         0
     };
 
+    let hours_worked = estimate_hours(repo_path, DEFAULT_SESSION_GAP_HOURS, DEFAULT_SESSION_PAD_MINUTES)
+        .unwrap_or(0.0);
+
     Ok(GitStatus {
         is_repo: true,
         branch,
@@ -177,49 +202,172 @@ pub fn get_git_status(repo_path: &str) -> Result<GitStatus, String> {
         days_since_commit,
         last_commit_message,
         last_commit_date,
+        hours_worked,
     })
 }
 
-/// Find duplicate files in the repository
-pub fn find_duplicates(files: &[FileEntry]) -> Vec<DuplicateFile> {
-    use std::collections::hash_map::DefaultHasher;
-    use std::fs;
-    use std::hash::{Hash, Hasher};
+/// Default gap (in hours) beyond which two consecutive commits are treated
+/// as belonging to separate work sessions rather than one continuous sitting.
+const DEFAULT_SESSION_GAP_HOURS: f64 = 2.0;
 
-    let mut content_map: HashMap<u64, Vec<String>> = HashMap::new();
+/// Fixed time credited for the first commit of a session, since we have no
+/// way to know how long the developer worked before that commit landed.
+const DEFAULT_SESSION_PAD_MINUTES: f64 = 30.0;
 
-    for file in files {
-        if let Ok(content) = fs::read_to_string(&file.path) {
-            let mut hasher = DefaultHasher::new();
-            content.hash(&mut hasher);
-            let hash = hasher.finish();
-
-            content_map
-                .entry(hash)
-                .or_insert_with(Vec::new)
-                .push(file.path.clone());
+/// Estimate total developer time invested in the repo from commit
+/// timestamps, the way git-hours does: group commits per author, sort each
+/// author's timestamps ascending, and walk consecutive pairs. A gap under
+/// `max_gap_hours` is counted as time worked; a larger gap starts a new
+/// session and credits a fixed `session_pad_minutes` for it instead (since
+/// we can't see how long the developer worked before that first commit).
+/// Hours are summed across authors for the repo-wide total.
+pub fn estimate_hours(repo_path: &str, max_gap_hours: f64, session_pad_minutes: f64) -> Result<f64, String> {
+    let mut commits_by_author = git_backend::default_backend().commit_timestamps_by_author(repo_path)?;
+
+    let max_gap_seconds = (max_gap_hours * 3600.0) as i64;
+    let session_pad_hours = session_pad_minutes / 60.0;
+
+    let mut total_hours = 0.0;
+    for timestamps in commits_by_author.values_mut() {
+        timestamps.sort_unstable();
+
+        if timestamps.is_empty() {
+            continue;
+        }
+
+        // Every session (including the very first) credits the pad; back-to-back
+        // commits within the gap threshold add the actual elapsed time instead.
+        total_hours += session_pad_hours;
+        for pair in timestamps.windows(2) {
+            let gap_seconds = pair[1] - pair[0];
+            if gap_seconds <= max_gap_seconds {
+                total_hours += gap_seconds as f64 / 3600.0;
+            } else {
+                total_hours += session_pad_hours;
+            }
         }
     }
 
+    Ok(total_hours)
+}
+
+/// Find duplicate files in the repository
+pub fn find_duplicates(files: &[FileEntry]) -> Vec<DuplicateFile> {
+    // Bucket by size first (cheap, no I/O) so we only stream-hash files that
+    // could plausibly collide, instead of hashing the whole tree.
+    let mut by_size: HashMap<u64, Vec<&FileEntry>> = HashMap::new();
+    for file in files {
+        by_size.entry(file.size).or_default().push(file);
+    }
+
     let mut duplicates: Vec<DuplicateFile> = Vec::new();
 
-    for (hash, paths) in content_map {
-        if paths.len() > 1 {
-            let mut sorted_paths = paths.clone();
-            sorted_paths.sort_by(|a, b| a.len().cmp(&b.len()));
+    for group in by_size.values() {
+        if group.len() < 2 {
+            continue;
+        }
 
-            let original = sorted_paths.remove(0);
-            duplicates.push(DuplicateFile {
-                original,
-                duplicates: sorted_paths,
-                content_hash: format!("{:x}", hash),
-            });
+        let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+        for file in group {
+            if let Some(hash) = hash_file_contents(&file.path) {
+                by_hash.entry(hash).or_default().push(file.path.clone());
+            }
+        }
+
+        for (hash, mut paths) in by_hash {
+            if paths.len() > 1 {
+                paths.sort_by_key(|p| p.len());
+                let original = paths.remove(0);
+                duplicates.push(DuplicateFile {
+                    original,
+                    duplicates: paths,
+                    content_hash: hash,
+                });
+            }
         }
     }
 
     duplicates
 }
 
+/// Stream a file's bytes through blake3 rather than reading it into a
+/// `String` first, so binary files (images, archives) get deduped too
+/// instead of being silently skipped by a lossy UTF-8 read.
+fn hash_file_contents(path: &str) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(hasher.finalize().to_hex().to_string())
+}
+
+/// Number of smallest shingle hashes kept per file for the MinHash sketch.
+const MINHASH_SKETCH_SIZE: usize = 64;
+
+/// Lines per shingle; a small window stays robust to single-line edits
+/// scattered through an otherwise near-identical file.
+const SHINGLE_LINE_COUNT: usize = 3;
+
+/// Hash every `SHINGLE_LINE_COUNT`-line window of `path`'s content and keep
+/// the `MINHASH_SKETCH_SIZE` smallest hashes, approximating the file's
+/// k-gram set without storing it in full (a bottom-k MinHash sketch).
+fn minhash_sketch(path: &str) -> Option<Vec<u64>> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let window_size = SHINGLE_LINE_COUNT.min(lines.len());
+    let mut hashes: Vec<u64> = lines
+        .windows(window_size)
+        .map(|window| {
+            let shingle = window.join("\n");
+            let digest = blake3::hash(shingle.as_bytes());
+            u64::from_le_bytes(digest.as_bytes()[..8].try_into().unwrap())
+        })
+        .collect();
+
+    hashes.sort_unstable();
+    hashes.dedup();
+    hashes.truncate(MINHASH_SKETCH_SIZE);
+    Some(hashes)
+}
+
+/// Estimate Jaccard similarity between two files from their MinHash
+/// sketches: the fraction of the larger sketch's hashes that also appear in
+/// the other file's sketch.
+fn estimate_similarity(a: &[u64], b: &[u64]) -> f32 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let b_set: std::collections::HashSet<u64> = b.iter().copied().collect();
+    let shared = a.iter().filter(|hash| b_set.contains(hash)).count();
+    shared as f32 / a.len().max(b.len()) as f32
+}
+
+/// A percentage-backed reason for how a copy-pattern file relates to its
+/// inferred original, or `None` if either file couldn't be read.
+fn similarity_reason(file_path: &str, original_path: &str) -> Option<String> {
+    let sketch_a = minhash_sketch(file_path)?;
+    let sketch_b = minhash_sketch(original_path)?;
+    let similarity = (estimate_similarity(&sketch_a, &sketch_b) * 100.0).round() as u32;
+
+    let original_name = Path::new(original_path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(original_path);
+
+    Some(if similarity >= 85 {
+        format!("{}% identical to {} — safe to delete?", similarity, original_name)
+    } else {
+        format!("only {}% similar to {} — diverged, review before deleting", similarity, original_name)
+    })
+}
+
 /// Detect copy/backup naming patterns
 pub fn detect_copy_patterns(files: &[FileEntry]) -> Vec<FileSuggestion> {
     let copy_patterns = [
@@ -229,6 +377,8 @@ pub fn detect_copy_patterns(files: &[FileEntry]) -> Vec<FileSuggestion> {
         "_this_one_works", "_latest", "_LATEST",
     ];
 
+    let by_path: HashMap<&str, &FileEntry> = files.iter().map(|f| (f.path.as_str(), f)).collect();
+
     let mut suggestions: Vec<FileSuggestion> = Vec::new();
     let mut pattern_counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
 
@@ -242,7 +392,7 @@ pub fn detect_copy_patterns(files: &[FileEntry]) -> Vec<FileSuggestion> {
         for pattern in &copy_patterns {
             if stem.to_lowercase().contains(&pattern.to_lowercase()) {
                 *pattern_counts.entry((*pattern).to_string()).or_insert(0) += 1;
-                
+
                 let potential_original = stem.replace(pattern, "");
                 let ext = Path::new(name)
                     .extension()
@@ -256,11 +406,21 @@ pub fn detect_copy_patterns(files: &[FileEntry]) -> Vec<FileSuggestion> {
                     _ => format!("{}th file with '{}' in the name. At this point it's a collection. 📋📋📋", pattern_counts.get(*pattern).unwrap_or(&1), pattern),
                 };
 
+                let original_path = Path::new(&file.path)
+                    .parent()
+                    .map(|dir| dir.join(format!("{}.{}", potential_original, ext)))
+                    .map(|p| p.to_string_lossy().to_string())
+                    .filter(|p| by_path.contains_key(p.as_str()) && p != &file.path);
+
+                let reason = original_path
+                    .and_then(|original_path| similarity_reason(&file.path, &original_path))
+                    .unwrap_or(snarky_reason);
+
                 suggestions.push(FileSuggestion {
                     file_path: file.path.clone(),
                     suggestion: format!("Looks like a copy of '{}.{}'", potential_original, ext),
                     action: "review".to_string(),
-                    reason: snarky_reason,
+                    reason,
                 });
                 break;
             }
@@ -270,20 +430,120 @@ pub fn detect_copy_patterns(files: &[FileEntry]) -> Vec<FileSuggestion> {
     suggestions
 }
 
+/// A changed file from `git status --porcelain`, kept alongside its two
+/// status characters so callers can tell a brand new file from an edit.
+type StatusedFile = (String, String); // (status_code, file_path)
+
+/// Check whether the diff for `file_path` adds a new public item (a fresh
+/// export). This is what separates a `feat:` from a plain `fix:` for a file
+/// that isn't brand new.
+fn diff_adds_public_item(repo_path: &str, file_path: &str) -> bool {
+    let added_lines = git_backend::default_backend()
+        .added_lines(repo_path, file_path)
+        .unwrap_or_default();
+
+    const NEW_PUBLIC_ITEM_PREFIXES: [&str; 6] = [
+        "pub fn ", "pub struct ", "pub enum ", "pub trait ",
+        "export function ", "export const ",
+    ];
+
+    added_lines
+        .iter()
+        .any(|added| NEW_PUBLIC_ITEM_PREFIXES.iter().any(|prefix| added.starts_with(prefix)))
+}
+
+/// Classify a single changed file into a conventional-commit type and the
+/// semver bump it implies, the way versio's change analysis does:
+/// doc/config/test-only changes don't move the version; a new file or a
+/// newly exported item is a feature (minor bump); anything else touching
+/// existing logic is a fix (patch bump).
+fn classify_change(repo_path: &str, status_code: &str, file_path: &str) -> (&'static str, SemverBump) {
+    let ext = Path::new(file_path).extension().and_then(|e| e.to_str()).unwrap_or("");
+    let stem = Path::new(file_path).file_stem().and_then(|s| s.to_str()).unwrap_or("");
+    let is_test = file_path.contains("test/") || file_path.contains("tests/")
+        || stem.starts_with("test_") || stem.ends_with("_test") || stem.ends_with(".test");
+
+    if is_test {
+        return ("test", SemverBump::None);
+    }
+    if ["md", "txt", "rst", "doc"].contains(&ext) {
+        return ("docs", SemverBump::None);
+    }
+    if ["json", "yaml", "yml", "toml", "ini", "cfg"].contains(&ext) {
+        return ("chore", SemverBump::None);
+    }
+
+    let is_new_file = status_code.contains('A') || status_code.contains('?');
+    let is_new_feature = (is_new_file && file_path.contains("src/"))
+        || diff_adds_public_item(repo_path, file_path);
+
+    if is_new_feature {
+        ("feat", SemverBump::Minor)
+    } else {
+        ("fix", SemverBump::Patch)
+    }
+}
+
+/// How "significant" a conventional-commit type is, for picking the
+/// dominant type across a group of files that don't all classify the same.
+fn commit_type_rank(commit_type: &str) -> u8 {
+    match commit_type {
+        "feat" => 4,
+        "fix" => 3,
+        "test" => 2,
+        "chore" => 1,
+        _ => 0, // docs
+    }
+}
+
+/// Classify every file in a group and fold the results into the single
+/// highest-ranked commit type and the highest semver bump among them.
+fn classify_group(repo_path: &str, entries: &[StatusedFile]) -> (&'static str, SemverBump) {
+    entries
+        .iter()
+        .map(|(status_code, file_path)| classify_change(repo_path, status_code, file_path))
+        .fold(("docs", SemverBump::None), |(best_type, best_bump), (commit_type, bump)| {
+            let commit_type = if commit_type_rank(commit_type) > commit_type_rank(best_type) { commit_type } else { best_type };
+            (commit_type, best_bump.max(bump))
+        })
+}
+
+/// Pick the directory name most of `files` live in, for use as a
+/// conventional-commit scope when a group spans more than one directory.
+fn dominant_scope(files: &[String]) -> String {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for file in files {
+        let dir = Path::new(file)
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or("root")
+            .to_string();
+        *counts.entry(dir).or_insert(0) += 1;
+    }
+    counts.into_iter().max_by_key(|(_, count)| *count).map(|(dir, _)| dir).unwrap_or_else(|| "root".to_string())
+}
+
+/// Render `type(scope): verb N files` in conventional-commit style.
+fn conventional_message(commit_type: &str, scope: &str, file_count: usize, bump: SemverBump) -> String {
+    let verb = match bump {
+        SemverBump::None => "update",
+        SemverBump::Patch => "fix",
+        SemverBump::Minor | SemverBump::Major => "add",
+    };
+    let plural = if file_count == 1 { "" } else { "s" };
+    format!("{}({}): {} {} file{}", commit_type, scope, verb, file_count, plural)
+}
+
 /// Generate smart commit suggestions based on file patterns
 pub fn suggest_commits(repo_path: &str) -> Result<Vec<CommitSuggestion>, String> {
-    let status_output = run_git_command(repo_path, &["status", "--porcelain"])?;
+    let status_entries = git_backend::default_backend().status_entries(repo_path)?;
 
-    let mut files_by_dir: HashMap<String, Vec<String>> = HashMap::new();
-    let mut files_by_ext: HashMap<String, Vec<String>> = HashMap::new();
-
-    for line in status_output.lines() {
-        if line.len() < 3 {
-            continue;
-        }
-        let file_path = line[3..].trim();
+    let mut files_by_dir: HashMap<String, Vec<StatusedFile>> = HashMap::new();
+    let mut files_by_ext: HashMap<String, Vec<StatusedFile>> = HashMap::new();
 
-        let dir = Path::new(file_path)
+    for StatusEntry { status_code, path: file_path } in status_entries {
+        let dir = Path::new(&file_path)
             .parent()
             .and_then(|p| p.to_str())
             .unwrap_or("root")
@@ -291,34 +551,35 @@ pub fn suggest_commits(repo_path: &str) -> Result<Vec<CommitSuggestion>, String>
 
         files_by_dir
             .entry(dir)
-            .or_insert_with(Vec::new)
-            .push(file_path.to_string());
+            .or_default()
+            .push((status_code.clone(), file_path.clone()));
 
-        let ext = Path::new(file_path)
+        let ext = Path::new(&file_path)
             .extension()
             .and_then(|e| e.to_str())
             .unwrap_or("none")
             .to_string();
 
-        files_by_ext
-            .entry(ext)
-            .or_insert_with(Vec::new)
-            .push(file_path.to_string());
+        files_by_ext.entry(ext).or_default().push((status_code, file_path));
     }
 
     let mut suggestions: Vec<CommitSuggestion> = Vec::new();
 
-    for (dir, files) in &files_by_dir {
-        if files.len() >= 2 {
+    for (dir, entries) in &files_by_dir {
+        if entries.len() >= 2 {
             let dir_name = Path::new(dir)
                 .file_name()
                 .and_then(|n| n.to_str())
                 .unwrap_or(dir);
+            let files: Vec<String> = entries.iter().map(|(_, f)| f.clone()).collect();
+            let (commit_type, bump) = classify_group(repo_path, entries);
 
             suggestions.push(CommitSuggestion {
-                files: files.clone(),
                 suggested_message: format!("Update {} files in {}", files.len(), dir_name),
+                conventional_message: conventional_message(commit_type, dir_name, files.len(), bump),
                 category: "feature".to_string(),
+                semver_bump: bump,
+                files,
             });
         }
     }
@@ -327,14 +588,17 @@ pub fn suggest_commits(repo_path: &str) -> Result<Vec<CommitSuggestion>, String>
     let config_files: Vec<String> = files_by_ext
         .iter()
         .filter(|(ext, _)| config_exts.contains(&ext.as_str()))
-        .flat_map(|(_, files)| files.clone())
+        .flat_map(|(_, entries)| entries.iter().map(|(_, f)| f.clone()))
         .collect();
 
     if config_files.len() >= 2 {
+        let scope = dominant_scope(&config_files);
         suggestions.push(CommitSuggestion {
-            files: config_files,
             suggested_message: "Update configuration files".to_string(),
+            conventional_message: conventional_message("chore", &scope, config_files.len(), SemverBump::None),
             category: "config".to_string(),
+            semver_bump: SemverBump::None,
+            files: config_files,
         });
     }
 
@@ -342,106 +606,189 @@ pub fn suggest_commits(repo_path: &str) -> Result<Vec<CommitSuggestion>, String>
     let doc_files: Vec<String> = files_by_ext
         .iter()
         .filter(|(ext, _)| doc_exts.contains(&ext.as_str()))
-        .flat_map(|(_, files)| files.clone())
+        .flat_map(|(_, entries)| entries.iter().map(|(_, f)| f.clone()))
         .collect();
 
     if !doc_files.is_empty() {
+        let scope = dominant_scope(&doc_files);
         suggestions.push(CommitSuggestion {
-            files: doc_files,
             suggested_message: "Update documentation".to_string(),
+            conventional_message: conventional_message("docs", &scope, doc_files.len(), SemverBump::None),
             category: "docs".to_string(),
+            semver_bump: SemverBump::None,
+            files: doc_files,
         });
     }
 
     Ok(suggestions)
 }
 
+/// Synthesize a Conventional-Commits-style subject, plus a short body
+/// listing the touched paths, from a changeset. Reuses the same
+/// type/scope classification `suggest_commits` does, but against whatever
+/// `entries` the caller hands it (the staged diff for `commit`, or the
+/// full working-tree status for a `wip_commit` preview before anything's
+/// actually staged). Returns `None` for an empty changeset.
+fn synthesize_commit_message(repo_path: &str, entries: &[StatusedFile]) -> Option<String> {
+    if entries.is_empty() {
+        return None;
+    }
+
+    let files: Vec<String> = entries.iter().map(|(_, f)| f.clone()).collect();
+    let (commit_type, bump) = classify_group(repo_path, entries);
+    let scope = dominant_scope(&files);
+    let subject = conventional_message(commit_type, &scope, files.len(), bump);
+
+    let mut sorted_files = files.clone();
+    sorted_files.sort();
+    const MAX_LISTED: usize = 10;
+    let mut body = sorted_files.iter().take(MAX_LISTED).cloned().collect::<Vec<_>>().join("\n");
+    if sorted_files.len() > MAX_LISTED {
+        body.push_str(&format!("\n...and {} more", sorted_files.len() - MAX_LISTED));
+    }
+
+    Some(format!("{}\n\n{}", subject, body))
+}
+
+/// Find every conflicted file and cross-check it for actual `<<<<<<<`
+/// `=======` `>>>>>>>` markers (a file can be listed as conflicted by git
+/// while already hand-edited clean).
+fn scan_conflicted_files(repo_path: &str) -> Vec<ConflictedFile> {
+    let Ok(paths) = git_backend::default_backend().conflicted_paths(repo_path) else {
+        return Vec::new();
+    };
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let has_conflict_markers = std::fs::read_to_string(Path::new(repo_path).join(&path))
+                .map(|content| {
+                    content.contains("<<<<<<<") && content.contains("=======") && content.contains(">>>>>>>")
+                })
+                .unwrap_or(false);
+            ConflictedFile { path, has_conflict_markers }
+        })
+        .collect()
+}
+
 /// Generate the full Clippy report
 pub fn generate_clippy_report(
     repo_path: &str,
     index_files: Option<&[FileEntry]>,
 ) -> Result<GitClippyReport, String> {
     let status = get_git_status(repo_path)?;
+    let operation_state = git_backend::default_backend().operation_state(repo_path);
 
     let mut suggestions: Vec<ClippySuggestion> = Vec::new();
     let mut duplicates: Vec<DuplicateFile> = Vec::new();
     let mut copy_pattern_files: Vec<FileSuggestion> = Vec::new();
 
-    // Check uncommitted files
-    if status.uncommitted_files > 200 {
+    // While a rebase/merge/cherry-pick/revert/bisect is in progress, don't
+    // nag about uncommitted files — the working directory is supposed to
+    // look like that right now. Surface state-appropriate buttons instead
+    // (see the `operation_state` block below).
+    if operation_state.is_none() {
+        if status.uncommitted_files > 200 {
+            suggestions.push(ClippySuggestion {
+                id: "extreme_uncommitted".to_string(),
+                icon: "🚨".to_string(),
+                title: format!("{} modified files in working directory", status.uncommitted_files),
+                description: format!(
+                    "📎 \"I notice you haven't committed in {} days.\n    Your working directory has {} modified files.\n    Should I...\"",
+                    status.days_since_commit, status.uncommitted_files
+                ),
+                actions: vec![
+                    ClippyAction {
+                        label: "Commit everything".to_string(),
+                        action_type: "wip_commit".to_string(),
+                        data: None,
+                    },
+                    ClippyAction {
+                        label: "Create panic backup".to_string(),
+                        action_type: "panic_backup".to_string(),
+                        data: None,
+                    },
+                    ClippyAction {
+                        label: "Cry".to_string(),
+                        action_type: "cry".to_string(),
+                        data: None,
+                    },
+                ],
+                priority: 10,
+            });
+        } else if status.uncommitted_files > 50 {
+            suggestions.push(ClippySuggestion {
+                id: "many_uncommitted".to_string(),
+                icon: "⚠️".to_string(),
+                title: format!("{} uncommitted files", status.uncommitted_files),
+                description:
+                    "That's a lot of changes. Your future self might thank you for committing. Or at least making a backup before Claude starts making copies..."
+                        .to_string(),
+                actions: vec![
+                    ClippyAction {
+                        label: "Smart commit".to_string(),
+                        action_type: "commit".to_string(),
+                        data: None,
+                    },
+                    ClippyAction {
+                        label: "Review changes".to_string(),
+                        action_type: "review".to_string(),
+                        data: None,
+                    },
+                    ClippyAction {
+                        label: "Panic mode (commit all as WIP)".to_string(),
+                        action_type: "wip_commit".to_string(),
+                        data: None,
+                    },
+                ],
+                priority: 4,
+            });
+        } else if status.uncommitted_files > 10 {
+            suggestions.push(ClippySuggestion {
+                id: "some_uncommitted".to_string(),
+                icon: "📝".to_string(),
+                title: format!("{} uncommitted files", status.uncommitted_files),
+                description: "Good progress! Consider committing related changes together.".to_string(),
+                actions: vec![
+                    ClippyAction {
+                        label: "Smart commit".to_string(),
+                        action_type: "commit".to_string(),
+                        data: None,
+                    },
+                    ClippyAction {
+                        label: "Later".to_string(),
+                        action_type: "dismiss".to_string(),
+                        data: None,
+                    },
+                ],
+                priority: 2,
+            });
+        }
+    }
+
+    // Check total developer time invested in the branch
+    if status.hours_worked > 8.0 {
         suggestions.push(ClippySuggestion {
-            id: "extreme_uncommitted".to_string(),
-            icon: "🚨".to_string(),
-            title: format!("{} modified files in working directory", status.uncommitted_files),
+            id: "long_session_uncommitted".to_string(),
+            icon: "⏳".to_string(),
+            title: format!("~{:.1}h invested in this branch", status.hours_worked),
             description: format!(
-                "📎 \"I notice you haven't committed in {} days.\n    Your working directory has {} modified files.\n    Should I...\"",
-                status.days_since_commit, status.uncommitted_files
+                "📎 \"You've put roughly {:.1} hours into this branch.\n    Maybe commit before you lose it?\"",
+                status.hours_worked
             ),
             actions: vec![
                 ClippyAction {
-                    label: "Commit everything".to_string(),
-                    action_type: "wip_commit".to_string(),
-                    data: None,
-                },
-                ClippyAction {
-                    label: "Create panic backup".to_string(),
-                    action_type: "panic_backup".to_string(),
-                    data: None,
-                },
-                ClippyAction {
-                    label: "Cry".to_string(),
-                    action_type: "cry".to_string(),
-                    data: None,
-                },
-            ],
-            priority: 10,
-        });
-    } else if status.uncommitted_files > 50 {
-        suggestions.push(ClippySuggestion {
-            id: "many_uncommitted".to_string(),
-            icon: "⚠️".to_string(),
-            title: format!("{} uncommitted files", status.uncommitted_files),
-            description:
-                "That's a lot of changes. Your future self might thank you for committing. Or at least making a backup before Claude starts making copies..."
-                    .to_string(),
-            actions: vec![
-                ClippyAction {
-                    label: "Smart commit".to_string(),
-                    action_type: "commit".to_string(),
-                    data: None,
-                },
-                ClippyAction {
-                    label: "Review changes".to_string(),
-                    action_type: "review".to_string(),
-                    data: None,
-                },
-                ClippyAction {
-                    label: "Panic mode (commit all as WIP)".to_string(),
-                    action_type: "wip_commit".to_string(),
-                    data: None,
-                },
-            ],
-            priority: 4,
-        });
-    } else if status.uncommitted_files > 10 {
-        suggestions.push(ClippySuggestion {
-            id: "some_uncommitted".to_string(),
-            icon: "📝".to_string(),
-            title: format!("{} uncommitted files", status.uncommitted_files),
-            description: "Good progress! Consider committing related changes together.".to_string(),
-            actions: vec![
-                ClippyAction {
-                    label: "Smart commit".to_string(),
+                    label: "Commit now".to_string(),
                     action_type: "commit".to_string(),
                     data: None,
                 },
                 ClippyAction {
-                    label: "Later".to_string(),
+                    label: "I've got this".to_string(),
                     action_type: "dismiss".to_string(),
                     data: None,
                 },
             ],
-            priority: 2,
+            priority: 6,
         });
     }
 
@@ -601,66 +948,146 @@ pub fn generate_clippy_report(
         });
     }
 
-    // Check for merge conflicts or merge in progress
-    let merge_head = Path::new(repo_path).join(".git").join("MERGE_HEAD");
-    if merge_head.exists() {
+    // An in-progress rebase/merge/cherry-pick/revert/bisect gets its own
+    // state-appropriate suggestion (continue/abort/skip) instead of the
+    // generic ones above.
+    let conflicted_files = if operation_state.is_some() {
+        scan_conflicted_files(repo_path)
+    } else {
+        Vec::new()
+    };
+
+    if let Some(ref state) = operation_state {
+        let unresolved = conflicted_files.len();
+        let conflict_note = if unresolved > 0 {
+            format!(" ({} file{} still conflicted)", unresolved, if unresolved == 1 { "" } else { "s" })
+        } else {
+            String::new()
+        };
+
+        let (id, icon, title, description, mut actions, priority): (&str, &str, String, String, Vec<ClippyAction>, u8) = match state {
+            GitOperationState::Merging => (
+                "merge_in_progress",
+                "🏃",
+                format!("It looks like you're trying to merge!{}", conflict_note),
+                "📎 \"It looks like you're trying to merge!\n    Just kidding, I'm backing away slowly.\n    You're on your own with this one. Good luck! 🏃\"\n\n(But seriously, finish the merge or abort it)".to_string(),
+                vec![
+                    ClippyAction { label: "I know what I'm doing".to_string(), action_type: "dismiss".to_string(), data: None },
+                    ClippyAction { label: "Abort merge".to_string(), action_type: "abort_merge".to_string(), data: None },
+                    ClippyAction { label: "Pray".to_string(), action_type: "pray".to_string(), data: None },
+                ],
+                8,
+            ),
+            GitOperationState::Rebasing { .. } => (
+                "rebase_in_progress",
+                "🎢",
+                format!("Rebase in progress ({}){}", state.label(), conflict_note),
+                "You started a rebase. No pressure, but you should probably finish it before doing anything else.".to_string(),
+                vec![
+                    ClippyAction { label: "Continue rebase".to_string(), action_type: "rebase_continue".to_string(), data: None },
+                    ClippyAction { label: "Abort rebase".to_string(), action_type: "rebase_abort".to_string(), data: None },
+                ],
+                9,
+            ),
+            GitOperationState::CherryPicking => (
+                "cherry_pick_in_progress",
+                "🍒",
+                format!("Cherry-pick in progress{}", conflict_note),
+                "You're halfway through a cherry-pick. Finish it or back out before doing anything else.".to_string(),
+                vec![
+                    ClippyAction { label: "Continue cherry-pick".to_string(), action_type: "cherry_pick_continue".to_string(), data: None },
+                    ClippyAction { label: "Abort cherry-pick".to_string(), action_type: "cherry_pick_abort".to_string(), data: None },
+                ],
+                9,
+            ),
+            GitOperationState::Reverting => (
+                "revert_in_progress",
+                "⏪",
+                format!("Revert in progress{}", conflict_note),
+                "A revert is waiting on you. Finish it or abort before moving on.".to_string(),
+                vec![
+                    ClippyAction { label: "Continue revert".to_string(), action_type: "revert_continue".to_string(), data: None },
+                    ClippyAction { label: "Abort revert".to_string(), action_type: "revert_abort".to_string(), data: None },
+                ],
+                9,
+            ),
+            GitOperationState::Bisecting => (
+                "bisect_in_progress",
+                "🔍",
+                "Bisect in progress".to_string(),
+                "You're mid-bisect. Mark this commit good/bad or give up and abort.".to_string(),
+                vec![
+                    ClippyAction { label: "Skip this commit".to_string(), action_type: "bisect_skip".to_string(), data: None },
+                    ClippyAction { label: "Abort bisect".to_string(), action_type: "bisect_abort".to_string(), data: None },
+                ],
+                9,
+            ),
+        };
+
+        // The "continue" action only lights up once every conflict is
+        // resolved — there's nothing to continue into otherwise.
+        if unresolved > 0 {
+            actions.retain(|action| {
+                !matches!(action.action_type.as_str(), "rebase_continue" | "cherry_pick_continue" | "revert_continue")
+            });
+        }
+
         suggestions.push(ClippySuggestion {
-            id: "merge_in_progress".to_string(),
-            icon: "🏃".to_string(),
-            title: "It looks like you're trying to merge!".to_string(),
-            description: "📎 \"It looks like you're trying to merge!\n    Just kidding, I'm backing away slowly.\n    You're on your own with this one. Good luck! 🏃\"\n\n(But seriously, finish the merge or abort it)".to_string(),
-            actions: vec![
-                ClippyAction {
-                    label: "I know what I'm doing".to_string(),
-                    action_type: "dismiss".to_string(),
-                    data: None,
-                },
-                ClippyAction {
-                    label: "Abort merge".to_string(),
-                    action_type: "abort_merge".to_string(),
-                    data: None,
-                },
-                ClippyAction {
-                    label: "Pray".to_string(),
-                    action_type: "pray".to_string(),
-                    data: None,
-                },
-            ],
-            priority: 8,
+            id: id.to_string(),
+            icon: icon.to_string(),
+            title,
+            description,
+            actions,
+            priority,
         });
-    }
 
-    // Check for rebase in progress
-    let rebase_merge = Path::new(repo_path).join(".git").join("rebase-merge");
-    let rebase_apply = Path::new(repo_path).join(".git").join("rebase-apply");
-    if rebase_merge.exists() || rebase_apply.exists() {
-        suggestions.push(ClippySuggestion {
-            id: "rebase_in_progress".to_string(),
-            icon: "🎢".to_string(),
-            title: "Rebase in progress".to_string(),
-            description: "You started a rebase. No pressure, but you should probably finish it before doing anything else.".to_string(),
-            actions: vec![
-                ClippyAction {
-                    label: "Continue rebase".to_string(),
-                    action_type: "rebase_continue".to_string(),
-                    data: None,
-                },
-                ClippyAction {
-                    label: "Abort rebase".to_string(),
-                    action_type: "rebase_abort".to_string(),
-                    data: None,
+        for conflict in &conflicted_files {
+            suggestions.push(ClippySuggestion {
+                id: format!("conflict:{}", conflict.path),
+                icon: "⚔️".to_string(),
+                title: format!("Conflict in {}", conflict.path),
+                description: if conflict.has_conflict_markers {
+                    "Still has unresolved conflict markers.".to_string()
+                } else {
+                    "Marked conflicted, but no conflict markers found — probably just needs staging.".to_string()
                 },
-            ],
-            priority: 9,
-        });
+                actions: vec![
+                    ClippyAction {
+                        label: "Take ours".to_string(),
+                        action_type: "resolve_ours".to_string(),
+                        data: Some(serde_json::json!({"path": conflict.path})),
+                    },
+                    ClippyAction {
+                        label: "Take theirs".to_string(),
+                        action_type: "resolve_theirs".to_string(),
+                        data: Some(serde_json::json!({"path": conflict.path})),
+                    },
+                    ClippyAction {
+                        label: "Open file".to_string(),
+                        action_type: "open".to_string(),
+                        data: Some(serde_json::json!({"path": conflict.path})),
+                    },
+                    ClippyAction {
+                        label: "Mark resolved".to_string(),
+                        action_type: "mark_resolved".to_string(),
+                        data: Some(serde_json::json!({"path": conflict.path})),
+                    },
+                ],
+                priority: 7,
+            });
+        }
     }
 
     let commit_suggestions = suggest_commits(repo_path).unwrap_or_default();
 
     suggestions.sort_by(|a, b| b.priority.cmp(&a.priority));
 
-    // Enhanced urgency levels
-    let urgency_level = if status.days_since_commit > 7 && status.uncommitted_files > 200 {
+    // Enhanced urgency levels. A git operation in progress takes priority
+    // over the uncommitted-files classification — it's its own kind of
+    // urgent, and the commit-nagging levels below don't apply mid-operation.
+    let urgency_level = if operation_state.is_some() {
+        "mid_operation"
+    } else if status.days_since_commit > 7 && status.uncommitted_files > 200 {
         "existential_crisis"
     } else if status.days_since_commit > 7 && status.uncommitted_files > 50 {
         "panic"
@@ -673,6 +1100,10 @@ pub fn generate_clippy_report(
     };
 
     let message = match urgency_level {
+        "mid_operation" => format!(
+            "📎 {} — finish this before anything else.",
+            operation_state.as_ref().map(|s| s.label()).unwrap_or_default()
+        ),
         "existential_crisis" => format!(
             "📎 Oh no. {} days and {} files.\n    At what point do we just backup and start fresh?\n    I'm not judging. I'm just... concerned. 😰",
             status.days_since_commit, status.uncommitted_files
@@ -686,6 +1117,16 @@ pub fn generate_clippy_report(
         _ => "📎 All clear! You're doing great. ✨".to_string(),
     };
 
+    // Aggregate the highest semver impact across all suggested commits into
+    // a single report-level note.
+    let highest_bump = commit_suggestions.iter().map(|c| c.semver_bump).max().unwrap_or(SemverBump::None);
+    let suggested_next_version = match highest_bump {
+        SemverBump::Major => Some("Suggested next version bump: major (breaking changes detected)".to_string()),
+        SemverBump::Minor => Some("Suggested next version bump: minor (new features detected)".to_string()),
+        SemverBump::Patch => Some("Suggested next version bump: patch (fixes only)".to_string()),
+        SemverBump::None => None,
+    };
+
     Ok(GitClippyReport {
         status,
         urgency_level: urgency_level.to_string(),
@@ -694,41 +1135,154 @@ pub fn generate_clippy_report(
         duplicates,
         commit_suggestions,
         copy_pattern_files,
+        suggested_next_version,
+        operation_state,
+        conflicted_files,
     })
 }
 
+/// A `GitClippyReport` for one project discovered inside a monorepo.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProjectReport {
+    pub project_path: String,
+    pub report: GitClippyReport,
+}
+
+/// A clippy report for every project in a monorepo, with a rolled-up
+/// urgency level (the most urgent individual project's level).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MonorepoReport {
+    pub projects: Vec<ProjectReport>,
+    pub urgency_level: String,
+}
+
+/// Manifest files that mark a directory as an independent project root.
+const PROJECT_MANIFESTS: [&str; 3] = ["Cargo.toml", "package.json", "pyproject.toml"];
+
+/// Walk `root` and return every directory that looks like an independent
+/// project: one containing its own `.git` directory, or a recognized
+/// manifest file. Lets the assistant treat a workspace of many sub-projects
+/// as many projects instead of one giant repo.
+pub fn discover_projects(root: &str) -> Vec<String> {
+    let root_path = Path::new(root);
+    if !root_path.exists() {
+        return Vec::new();
+    }
+
+    let mut projects = Vec::new();
+
+    for entry in WalkDir::new(root_path)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| {
+            !matches!(e.file_name().to_str(), Some("node_modules" | "target" | ".git"))
+        })
+        .filter_map(|e| e.ok())
+    {
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+
+        let dir = entry.path();
+        let is_project = dir.join(".git").exists()
+            || PROJECT_MANIFESTS.iter().any(|manifest| dir.join(manifest).exists());
+
+        if is_project {
+            projects.push(dir.to_string_lossy().to_string());
+        }
+    }
+
+    projects
+}
+
+/// How urgent a `GitClippyReport`'s `urgency_level` is, for picking the
+/// single most urgent level across every project in a monorepo.
+fn urgency_rank(level: &str) -> u8 {
+    match level {
+        "mid_operation" => 5,
+        "existential_crisis" => 4,
+        "panic" => 3,
+        "warning" => 2,
+        "nudge" => 1,
+        _ => 0, // chill
+    }
+}
+
+/// Run `generate_clippy_report` against every project discovered under
+/// `root`, keyed by project path, so a monorepo points out which specific
+/// sub-project has 200 uncommitted files rather than treating the whole
+/// tree as one blob.
+pub fn generate_monorepo_report(root: &str) -> MonorepoReport {
+    let mut projects: Vec<ProjectReport> = discover_projects(root)
+        .into_iter()
+        .filter_map(|project_path| {
+            generate_clippy_report(&project_path, None)
+                .ok()
+                .map(|report| ProjectReport { project_path, report })
+        })
+        .collect();
+
+    projects.sort_by(|a, b| a.project_path.cmp(&b.project_path));
+
+    let urgency_level = projects
+        .iter()
+        .max_by_key(|p| urgency_rank(&p.report.urgency_level))
+        .map(|p| p.report.urgency_level.clone())
+        .unwrap_or_else(|| "chill".to_string());
+
+    MonorepoReport { projects, urgency_level }
+}
+
 /// Execute a git action
 pub fn execute_git_action(
     repo_path: &str,
     action: &str,
     data: Option<&serde_json::Value>,
 ) -> Result<String, String> {
+    let dry_run = data
+        .and_then(|d| d.get("dry_run"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
     match action {
         "wip_commit" => {
-            run_git_command(repo_path, &["add", "-A"])?;
-            run_git_command(repo_path, &["commit", "-m", "WIP: Work in progress save"])
-        }
-        "panic_backup" => {
-            // Create a timestamped backup branch
-            let backup_name = format!("panic-backup-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
-            run_git_command(repo_path, &["add", "-A"])?;
-            run_git_command(repo_path, &["stash", "push", "-m", &format!("Panic backup {}", backup_name)])?;
-            Ok(format!("📎 Created panic backup stash. Use 'git stash list' to see it. Breathe. It's going to be okay. 🫂"))
+            let backend = git_backend::default_backend();
+            let override_message = data.and_then(|d| d.get("message")).and_then(|m| m.as_str());
+            let message = match override_message {
+                Some(m) => m.to_string(),
+                None => {
+                    let status_entries = backend.status_entries(repo_path).unwrap_or_default();
+                    let statused: Vec<StatusedFile> = status_entries
+                        .into_iter()
+                        .map(|StatusEntry { status_code, path }| (status_code, path))
+                        .collect();
+                    synthesize_commit_message(repo_path, &statused)
+                        .unwrap_or_else(|| "WIP: Work in progress save".to_string())
+                }
+            };
+            backend.wip_commit(repo_path, &message, dry_run)
         }
+        "panic_backup" => git_backend::default_backend().panic_backup(repo_path, dry_run),
         "create_branch" => {
             let branch_name = data
                 .and_then(|d| d.get("name"))
                 .and_then(|n| n.as_str())
                 .unwrap_or("feature-branch");
-            run_git_command(repo_path, &["checkout", "-b", branch_name])
+            git_backend::default_backend().create_branch(repo_path, branch_name, dry_run)
         }
-        "stage_all" => run_git_command(repo_path, &["add", "-A"]),
+        "stage_all" => git_backend::default_backend().stage_all(repo_path, dry_run),
         "commit" => {
-            let message = data
-                .and_then(|d| d.get("message"))
-                .and_then(|m| m.as_str())
-                .unwrap_or("Update files");
-            run_git_command(repo_path, &["commit", "-m", message])
+            let backend = git_backend::default_backend();
+            let override_message = data.and_then(|d| d.get("message")).and_then(|m| m.as_str());
+            let message = match override_message {
+                Some(m) => m.to_string(),
+                None => {
+                    let staged = backend.staged_changes(repo_path).unwrap_or_default();
+                    synthesize_commit_message(repo_path, &staged)
+                        .unwrap_or_else(|| "Update files".to_string())
+                }
+            };
+            backend.commit(repo_path, &message, dry_run)
         }
         "git_init" => run_git_command(repo_path, &["init"]),
         "abort_merge" => {
@@ -740,6 +1294,40 @@ pub fn execute_git_action(
             run_git_command(repo_path, &["rebase", "--abort"])?;
             Ok("📎 Rebase aborted. Sometimes the bravest thing is to walk away. 🚶".to_string())
         }
+        "cherry_pick_continue" => run_git_command(repo_path, &["cherry-pick", "--continue"]),
+        "cherry_pick_abort" => {
+            run_git_command(repo_path, &["cherry-pick", "--abort"])?;
+            Ok("📎 Cherry-pick aborted. No hard feelings. 🍒".to_string())
+        }
+        "revert_continue" => run_git_command(repo_path, &["revert", "--continue"]),
+        "revert_abort" => {
+            run_git_command(repo_path, &["revert", "--abort"])?;
+            Ok("📎 Revert aborted. Back to where you started. ⏪".to_string())
+        }
+        "bisect_skip" => run_git_command(repo_path, &["bisect", "skip"]),
+        "bisect_abort" => {
+            run_git_command(repo_path, &["bisect", "reset"])?;
+            Ok("📎 Bisect reset. You can hunt that bug another day. 🔍".to_string())
+        }
+        "resolve_ours" => {
+            let path = data.and_then(|d| d.get("path")).and_then(|p| p.as_str())
+                .ok_or_else(|| "Missing 'path' for resolve_ours".to_string())?;
+            run_git_command(repo_path, &["checkout", "--ours", path])?;
+            run_git_command(repo_path, &["add", path])?;
+            Ok(format!("📎 Took ours for {}.", path))
+        }
+        "resolve_theirs" => {
+            let path = data.and_then(|d| d.get("path")).and_then(|p| p.as_str())
+                .ok_or_else(|| "Missing 'path' for resolve_theirs".to_string())?;
+            run_git_command(repo_path, &["checkout", "--theirs", path])?;
+            run_git_command(repo_path, &["add", path])?;
+            Ok(format!("📎 Took theirs for {}.", path))
+        }
+        "mark_resolved" => {
+            let path = data.and_then(|d| d.get("path")).and_then(|p| p.as_str())
+                .ok_or_else(|| "Missing 'path' for mark_resolved".to_string())?;
+            run_git_command(repo_path, &["add", path])
+        }
         "cry" => {
             Ok("📎 *hands you a tissue* 🤧\n    It's okay. We've all been there.\n    When you're ready, try [Create panic backup].\n    No judgment here.".to_string())
         }
@@ -752,3 +1340,226 @@ pub fn execute_git_action(
         _ => Err(format!("Unknown action: {}", action)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> std::path::PathBuf {
+        static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!("git_assistant_test_{}_{}_{}", name, std::process::id(), id));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn file_entry(path: &std::path::Path, size: u64) -> FileEntry {
+        FileEntry {
+            path: path.to_string_lossy().to_string(),
+            name: path.file_name().unwrap().to_string_lossy().to_string(),
+            size,
+            modified: String::new(),
+            extension: path.extension().and_then(|e| e.to_str()).unwrap_or("").to_string(),
+            record_id: None,
+            parent_file: None,
+        }
+    }
+
+    #[test]
+    fn find_duplicates_groups_files_with_identical_content() {
+        let dir = test_dir("dup");
+        std::fs::write(dir.join("a.txt"), "same content").unwrap();
+        std::fs::write(dir.join("b.txt"), "same content").unwrap();
+        std::fs::write(dir.join("c.txt"), "different").unwrap();
+
+        let files = vec![
+            file_entry(&dir.join("a.txt"), 12),
+            file_entry(&dir.join("b.txt"), 12),
+            file_entry(&dir.join("c.txt"), 9),
+        ];
+
+        let duplicates = find_duplicates(&files);
+
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].original, dir.join("a.txt").to_string_lossy());
+        assert_eq!(duplicates[0].duplicates, vec![dir.join("b.txt").to_string_lossy().to_string()]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn find_duplicates_ignores_files_with_different_sizes() {
+        let dir = test_dir("no_dup");
+        std::fs::write(dir.join("a.txt"), "short").unwrap();
+        std::fs::write(dir.join("b.txt"), "much longer content").unwrap();
+
+        let files = vec![
+            file_entry(&dir.join("a.txt"), 5),
+            file_entry(&dir.join("b.txt"), 19),
+        ];
+
+        assert!(find_duplicates(&files).is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn hash_file_contents_is_stable_and_content_sensitive() {
+        let dir = test_dir("hash");
+        std::fs::write(dir.join("a.txt"), "hello").unwrap();
+        std::fs::write(dir.join("b.txt"), "hello").unwrap();
+        std::fs::write(dir.join("c.txt"), "world").unwrap();
+
+        let a = hash_file_contents(&dir.join("a.txt").to_string_lossy()).unwrap();
+        let b = hash_file_contents(&dir.join("b.txt").to_string_lossy()).unwrap();
+        let c = hash_file_contents(&dir.join("c.txt").to_string_lossy()).unwrap();
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn estimate_similarity_is_one_for_identical_sketches_and_zero_for_disjoint() {
+        let same = vec![1u64, 2, 3];
+        assert_eq!(estimate_similarity(&same, &same), 1.0);
+        assert_eq!(estimate_similarity(&[1, 2], &[3, 4]), 0.0);
+        assert_eq!(estimate_similarity(&[], &[]), 1.0);
+    }
+
+    #[test]
+    fn minhash_sketch_is_identical_for_identical_files() {
+        let dir = test_dir("minhash");
+        let content = "line one\nline two\nline three\nline four\n";
+        std::fs::write(dir.join("a.txt"), content).unwrap();
+        std::fs::write(dir.join("b.txt"), content).unwrap();
+
+        let sketch_a = minhash_sketch(&dir.join("a.txt").to_string_lossy()).unwrap();
+        let sketch_b = minhash_sketch(&dir.join("b.txt").to_string_lossy()).unwrap();
+
+        assert_eq!(sketch_a, sketch_b);
+        assert!(!sketch_a.is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn detect_copy_patterns_flags_backup_style_names() {
+        let dir = test_dir("copy_pattern");
+        std::fs::write(dir.join("report.docx"), "original").unwrap();
+        std::fs::write(dir.join("report_copy.docx"), "original").unwrap();
+
+        let files = vec![
+            file_entry(&dir.join("report.docx"), 8),
+            file_entry(&dir.join("report_copy.docx"), 8),
+        ];
+
+        let suggestions = detect_copy_patterns(&files);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].file_path, dir.join("report_copy.docx").to_string_lossy());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn detect_copy_patterns_ignores_ordinary_files() {
+        let files = vec![file_entry(Path::new("/tmp/report.docx"), 8)];
+        assert!(detect_copy_patterns(&files).is_empty());
+    }
+
+    #[test]
+    fn classify_change_treats_test_files_as_test_with_no_bump() {
+        let (commit_type, bump) = classify_change("/nonexistent/repo", "M ", "src/foo_test.rs");
+        assert_eq!(commit_type, "test");
+        assert_eq!(bump, SemverBump::None);
+    }
+
+    #[test]
+    fn classify_change_treats_docs_as_docs_with_no_bump() {
+        let (commit_type, bump) = classify_change("/nonexistent/repo", "M ", "README.md");
+        assert_eq!(commit_type, "docs");
+        assert_eq!(bump, SemverBump::None);
+    }
+
+    #[test]
+    fn classify_change_treats_new_src_file_as_feature() {
+        let (commit_type, bump) = classify_change("/nonexistent/repo", "??", "src/new_module.rs");
+        assert_eq!(commit_type, "feat");
+        assert_eq!(bump, SemverBump::Minor);
+    }
+
+    #[test]
+    fn classify_change_treats_modified_non_src_file_as_fix() {
+        let (commit_type, bump) = classify_change("/nonexistent/repo", "M ", "scripts/build.sh");
+        assert_eq!(commit_type, "fix");
+        assert_eq!(bump, SemverBump::Patch);
+    }
+
+    #[test]
+    fn commit_type_rank_orders_feat_above_fix_above_test_above_chore() {
+        assert!(commit_type_rank("feat") > commit_type_rank("fix"));
+        assert!(commit_type_rank("fix") > commit_type_rank("test"));
+        assert!(commit_type_rank("test") > commit_type_rank("chore"));
+        assert!(commit_type_rank("chore") > commit_type_rank("docs"));
+    }
+
+    #[test]
+    fn dominant_scope_picks_the_directory_with_the_most_files() {
+        let files = vec![
+            "src/commands.rs".to_string(),
+            "src/lib.rs".to_string(),
+            "docs/readme.md".to_string(),
+        ];
+        assert_eq!(dominant_scope(&files), "src");
+    }
+
+    #[test]
+    fn dominant_scope_falls_back_to_root_for_top_level_files() {
+        assert_eq!(dominant_scope(&["Cargo.toml".to_string()]), "root");
+    }
+
+    #[test]
+    fn conventional_message_formats_type_scope_and_pluralization() {
+        assert_eq!(conventional_message("feat", "src", 1, SemverBump::Minor), "feat(src): add 1 file");
+        assert_eq!(conventional_message("fix", "src", 3, SemverBump::Patch), "fix(src): fix 3 files");
+        assert_eq!(conventional_message("docs", "root", 2, SemverBump::None), "docs(root): update 2 files");
+    }
+
+    #[test]
+    fn scan_conflicted_files_returns_empty_outside_a_repo() {
+        let dir = test_dir("no_repo");
+        assert!(scan_conflicted_files(&dir.to_string_lossy()).is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn discover_projects_finds_manifests_but_skips_nested_node_modules() {
+        let dir = test_dir("monorepo");
+        std::fs::create_dir_all(dir.join("crate-a")).unwrap();
+        std::fs::write(dir.join("crate-a").join("Cargo.toml"), "[package]").unwrap();
+        std::fs::create_dir_all(dir.join("crate-a").join("node_modules").join("pkg")).unwrap();
+        std::fs::write(dir.join("crate-a").join("node_modules").join("pkg").join("package.json"), "{}").unwrap();
+
+        let projects = discover_projects(&dir.to_string_lossy());
+
+        assert_eq!(projects, vec![dir.join("crate-a").to_string_lossy().to_string()]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn discover_projects_returns_empty_for_a_missing_root() {
+        assert!(discover_projects("/definitely/does/not/exist").is_empty());
+    }
+
+    #[test]
+    fn urgency_rank_orders_mid_operation_above_everything_else() {
+        assert!(urgency_rank("mid_operation") > urgency_rank("existential_crisis"));
+        assert!(urgency_rank("existential_crisis") > urgency_rank("panic"));
+        assert!(urgency_rank("panic") > urgency_rank("warning"));
+        assert!(urgency_rank("warning") > urgency_rank("nudge"));
+        assert!(urgency_rank("nudge") > urgency_rank("chill"));
+    }
+}
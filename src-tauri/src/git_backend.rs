@@ -0,0 +1,828 @@
+// Pluggable git backend: read repo state either by shelling out to the
+// `git` CLI and parsing its porcelain output, or directly from the object
+// database via git2 (no `git` binary required).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// A single entry from `git status --porcelain`: the two-character status
+/// code (e.g. `"??"`, `" M"`, `"A "`) and the file path it refers to.
+#[derive(Debug, Clone)]
+pub struct StatusEntry {
+    pub status_code: String,
+    pub path: String,
+}
+
+/// The repository's most recent commit.
+#[derive(Debug, Clone)]
+pub struct LastCommit {
+    pub message: String,
+    /// `%ci`-shaped ("YYYY-MM-DD HH:MM:SS +ZZZZ"), matching git's own
+    /// `--format=%ci` output so existing date parsing keeps working.
+    pub date: String,
+}
+
+/// A git operation that's currently in progress and waiting to be continued
+/// or aborted, detected from the presence of state files/directories inside
+/// `.git` rather than parsed from porcelain output.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitOperationState {
+    /// `rebase-merge/` or `rebase-apply/` present. `current`/`total` come
+    /// from the `msgnum`/`end` progress files and are `None` together when
+    /// those files aren't there yet (e.g. an interrupted `git am`).
+    Rebasing { current: Option<u32>, total: Option<u32> },
+    /// `MERGE_HEAD` present.
+    Merging,
+    /// `CHERRY_PICK_HEAD` present.
+    CherryPicking,
+    /// `REVERT_HEAD` present.
+    Reverting,
+    /// `BISECT_LOG` present.
+    Bisecting,
+}
+
+impl GitOperationState {
+    /// A short uppercase label like `"REBASING 3/10"` or `"MERGING"`, for
+    /// surfacing directly in clippy messages.
+    pub fn label(&self) -> String {
+        match self {
+            GitOperationState::Rebasing { current: Some(current), total: Some(total) } => {
+                format!("REBASING {}/{}", current, total)
+            }
+            GitOperationState::Rebasing { .. } => "REBASING".to_string(),
+            GitOperationState::Merging => "MERGING".to_string(),
+            GitOperationState::CherryPicking => "CHERRY-PICKING".to_string(),
+            GitOperationState::Reverting => "REVERTING".to_string(),
+            GitOperationState::Bisecting => "BISECTING".to_string(),
+        }
+    }
+}
+
+/// Read a rebase directory's `msgnum`/`end` progress files. Both come back
+/// `None` together if `end` is missing (e.g. an interrupted `git am`),
+/// rather than reporting a half-known progress count.
+fn read_rebase_progress(rebase_dir: &Path) -> (Option<u32>, Option<u32>) {
+    let total = std::fs::read_to_string(rebase_dir.join("end"))
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok());
+
+    if total.is_none() {
+        return (None, None);
+    }
+
+    let current = std::fs::read_to_string(rebase_dir.join("msgnum"))
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok());
+
+    (current, total)
+}
+
+/// Inspect `repo_path`'s `.git` directory for an in-progress rebase, merge,
+/// cherry-pick, revert, or bisect. Shared by both backends since this is a
+/// filesystem question, not an object-database one.
+fn detect_operation_state(repo_path: &str) -> Option<GitOperationState> {
+    let git_dir = Path::new(repo_path).join(".git");
+
+    let rebase_merge = git_dir.join("rebase-merge");
+    if rebase_merge.exists() {
+        let (current, total) = read_rebase_progress(&rebase_merge);
+        return Some(GitOperationState::Rebasing { current, total });
+    }
+
+    let rebase_apply = git_dir.join("rebase-apply");
+    if rebase_apply.exists() {
+        let (current, total) = read_rebase_progress(&rebase_apply);
+        return Some(GitOperationState::Rebasing { current, total });
+    }
+
+    if git_dir.join("MERGE_HEAD").exists() {
+        return Some(GitOperationState::Merging);
+    }
+    if git_dir.join("CHERRY_PICK_HEAD").exists() {
+        return Some(GitOperationState::CherryPicking);
+    }
+    if git_dir.join("REVERT_HEAD").exists() {
+        return Some(GitOperationState::Reverting);
+    }
+    if git_dir.join("BISECT_LOG").exists() {
+        return Some(GitOperationState::Bisecting);
+    }
+
+    None
+}
+
+/// A git backend capable of answering the questions the clippy assistant
+/// needs without caring whether the answer came from a subprocess or the
+/// object database directly.
+pub trait GitBackend: Send + Sync {
+    /// The current branch name. `Git2Backend` reports `"unknown"` for an
+    /// unborn branch and `"HEAD"` on a detached HEAD; `ProcessBackend`
+    /// reports an empty string on a detached HEAD.
+    fn current_branch(&self, repo_path: &str) -> Result<String, String>;
+
+    /// Working-tree status entries, equivalent to `git status --porcelain`.
+    fn status_entries(&self, repo_path: &str) -> Result<Vec<StatusEntry>, String>;
+
+    /// The repo's most recent commit, or `None` if it has no commits yet.
+    fn last_commit(&self, repo_path: &str) -> Result<Option<LastCommit>, String>;
+
+    /// The in-progress rebase/merge/cherry-pick/revert/bisect, if any.
+    fn operation_state(&self, repo_path: &str) -> Option<GitOperationState>;
+
+    /// Paths with unresolved merge conflicts (porcelain's `U` diff-filter).
+    fn conflicted_paths(&self, repo_path: &str) -> Result<Vec<String>, String>;
+
+    /// Every commit's author email and unix timestamp, for session-based
+    /// time-invested estimation.
+    fn commit_timestamps_by_author(&self, repo_path: &str) -> Result<HashMap<String, Vec<i64>>, String>;
+
+    /// The content of added (`+`) lines in `file_path`'s current diff
+    /// against HEAD (staged or unstaged), used to tell a `feat:` from a
+    /// `fix:` for a file that isn't brand new.
+    fn added_lines(&self, repo_path: &str, file_path: &str) -> Result<Vec<String>, String>;
+
+    /// The staged changeset as `(status letter, path)` pairs, equivalent to
+    /// `git diff --cached --name-status` (`A`/`M`/`D`/`R`/`C`/`T`), used to
+    /// synthesize a commit message from what's actually about to be
+    /// committed.
+    fn staged_changes(&self, repo_path: &str) -> Result<Vec<(String, String)>, String>;
+
+    /// Stage every change in the working tree (`git add -A`). When
+    /// `dry_run` is set, report what would be staged without touching the
+    /// index.
+    fn stage_all(&self, repo_path: &str, dry_run: bool) -> Result<String, String>;
+
+    /// Commit the currently staged tree with `message`. When `dry_run` is
+    /// set, report what would be committed without creating the commit.
+    fn commit(&self, repo_path: &str, message: &str, dry_run: bool) -> Result<String, String>;
+
+    /// Create and check out a new branch named `branch_name` from HEAD.
+    /// When `dry_run` is set, report what would be created without
+    /// touching any refs.
+    fn create_branch(&self, repo_path: &str, branch_name: &str, dry_run: bool) -> Result<String, String>;
+
+    /// Stage everything and commit it with `message` as a work-in-progress
+    /// checkpoint. When `dry_run` is set, report what would happen without
+    /// touching the repo.
+    fn wip_commit(&self, repo_path: &str, message: &str, dry_run: bool) -> Result<String, String>;
+
+    /// Stage everything and stash it as a timestamped emergency backup.
+    /// When `dry_run` is set, report what would happen without touching
+    /// the repo.
+    fn panic_backup(&self, repo_path: &str, dry_run: bool) -> Result<String, String>;
+}
+
+/// Which `GitBackend` implementation to use.
+pub enum GitBackendKind {
+    /// Read directly from the object database via git2. No `git` binary
+    /// required; this is the default.
+    Native,
+    /// Shell out to the `git` CLI and parse its porcelain output.
+    Process,
+}
+
+/// Build a `GitBackend` of the requested kind.
+pub fn open_backend(kind: GitBackendKind) -> Box<dyn GitBackend> {
+    match kind {
+        GitBackendKind::Native => Box::new(Git2Backend),
+        GitBackendKind::Process => Box::new(ProcessBackend),
+    }
+}
+
+/// The backend the rest of the app should use unless it has a specific
+/// reason not to: native, since it needs no `git` binary on PATH and pays
+/// no process-spawn cost per call.
+pub fn default_backend() -> Box<dyn GitBackend> {
+    open_backend(GitBackendKind::Native)
+}
+
+/// Shells out to the `git` CLI and parses its porcelain text output.
+pub struct ProcessBackend;
+
+/// Run a git command and return output (with hidden window on Windows)
+fn run_git_command(repo_path: &str, args: &[&str]) -> Result<String, String> {
+    #[cfg(windows)]
+    use std::os::windows::process::CommandExt;
+
+    let mut cmd = Command::new("git");
+    cmd.args(args).current_dir(repo_path);
+
+    // Hide the console window on Windows
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let output = cmd.output().map_err(|e| format!("Failed to run git: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+impl GitBackend for ProcessBackend {
+    fn current_branch(&self, repo_path: &str) -> Result<String, String> {
+        Ok(run_git_command(repo_path, &["branch", "--show-current"])?
+            .trim()
+            .to_string())
+    }
+
+    fn status_entries(&self, repo_path: &str) -> Result<Vec<StatusEntry>, String> {
+        let status_output = run_git_command(repo_path, &["status", "--porcelain"])?;
+        Ok(status_output
+            .lines()
+            .filter(|line| line.len() >= 3)
+            .map(|line| StatusEntry {
+                status_code: line[..2].to_string(),
+                path: line[3..].trim().to_string(),
+            })
+            .collect())
+    }
+
+    fn last_commit(&self, repo_path: &str) -> Result<Option<LastCommit>, String> {
+        let message = run_git_command(repo_path, &["log", "-1", "--format=%s"]);
+        let date = run_git_command(repo_path, &["log", "-1", "--format=%ci"]);
+        match (message, date) {
+            (Ok(message), Ok(date)) => Ok(Some(LastCommit {
+                message: message.trim().to_string(),
+                date: date.trim().to_string(),
+            })),
+            _ => Ok(None),
+        }
+    }
+
+    fn operation_state(&self, repo_path: &str) -> Option<GitOperationState> {
+        detect_operation_state(repo_path)
+    }
+
+    fn conflicted_paths(&self, repo_path: &str) -> Result<Vec<String>, String> {
+        let output = run_git_command(repo_path, &["diff", "--name-only", "--diff-filter=U"])?;
+        Ok(output.lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect())
+    }
+
+    fn commit_timestamps_by_author(&self, repo_path: &str) -> Result<HashMap<String, Vec<i64>>, String> {
+        let log_output = run_git_command(repo_path, &["log", "--format=%at|%ae"])?;
+
+        let mut commits_by_author: HashMap<String, Vec<i64>> = HashMap::new();
+        for line in log_output.lines() {
+            let Some((timestamp_str, author)) = line.split_once('|') else {
+                continue;
+            };
+            let Ok(timestamp) = timestamp_str.parse::<i64>() else {
+                continue;
+            };
+            commits_by_author.entry(author.to_string()).or_default().push(timestamp);
+        }
+
+        Ok(commits_by_author)
+    }
+
+    fn added_lines(&self, repo_path: &str, file_path: &str) -> Result<Vec<String>, String> {
+        let diff = run_git_command(repo_path, &["diff", "HEAD", "--", file_path])
+            .map(|d| {
+                if d.is_empty() {
+                    run_git_command(repo_path, &["diff", "--cached", "--", file_path]).unwrap_or_default()
+                } else {
+                    d
+                }
+            })
+            .unwrap_or_default();
+
+        Ok(diff
+            .lines()
+            .filter(|line| line.starts_with('+') && !line.starts_with("+++"))
+            .map(|line| line.trim_start_matches('+').trim_start().to_string())
+            .collect())
+    }
+
+    fn staged_changes(&self, repo_path: &str) -> Result<Vec<(String, String)>, String> {
+        let output = run_git_command(repo_path, &["diff", "--cached", "--name-status"])?;
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let status = line.chars().next()?.to_string();
+                let path = line.rsplit('\t').next()?.trim().to_string();
+                if path.is_empty() { None } else { Some((status, path)) }
+            })
+            .collect())
+    }
+
+    fn stage_all(&self, repo_path: &str, dry_run: bool) -> Result<String, String> {
+        if dry_run {
+            let count = self.status_entries(repo_path)?.len();
+            return Ok(format!("[dry run] Would stage {} changed file(s).", count));
+        }
+        run_git_command(repo_path, &["add", "-A"])
+    }
+
+    fn commit(&self, repo_path: &str, message: &str, dry_run: bool) -> Result<String, String> {
+        if dry_run {
+            return Ok(format!("[dry run] Would commit staged changes with message: \"{}\"", message));
+        }
+        run_git_command(repo_path, &["commit", "-m", message])
+    }
+
+    fn create_branch(&self, repo_path: &str, branch_name: &str, dry_run: bool) -> Result<String, String> {
+        if dry_run {
+            return Ok(format!("[dry run] Would create and check out branch \"{}\".", branch_name));
+        }
+        run_git_command(repo_path, &["checkout", "-b", branch_name])
+    }
+
+    fn wip_commit(&self, repo_path: &str, message: &str, dry_run: bool) -> Result<String, String> {
+        if dry_run {
+            let count = self.status_entries(repo_path)?.len();
+            return Ok(format!(
+                "[dry run] Would stage {} changed file(s) and commit as \"{}\".",
+                count, message
+            ));
+        }
+        run_git_command(repo_path, &["add", "-A"])?;
+        run_git_command(repo_path, &["commit", "-m", message])
+    }
+
+    fn panic_backup(&self, repo_path: &str, dry_run: bool) -> Result<String, String> {
+        let backup_name = format!("panic-backup-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        if dry_run {
+            let count = self.status_entries(repo_path)?.len();
+            return Ok(format!(
+                "[dry run] Would stage {} changed file(s) and stash them as \"Panic backup {}\".",
+                count, backup_name
+            ));
+        }
+        run_git_command(repo_path, &["add", "-A"])?;
+        run_git_command(repo_path, &["stash", "push", "-m", &format!("Panic backup {}", backup_name)])?;
+        Ok("📎 Created panic backup stash. Use 'git stash list' to see it. Breathe. It's going to be okay. 🫂".to_string())
+    }
+}
+
+/// Reads repo state directly from the object database via git2, with no
+/// `git` binary required and no process-spawn cost per call.
+pub struct Git2Backend;
+
+fn status_code_chars(status: git2::Status) -> String {
+    if status.contains(git2::Status::WT_NEW) && !status.contains(git2::Status::INDEX_NEW) {
+        return "??".to_string();
+    }
+
+    let index_char = if status.contains(git2::Status::INDEX_NEW) {
+        'A'
+    } else if status.contains(git2::Status::INDEX_MODIFIED) {
+        'M'
+    } else if status.contains(git2::Status::INDEX_DELETED) {
+        'D'
+    } else if status.contains(git2::Status::INDEX_RENAMED) {
+        'R'
+    } else if status.contains(git2::Status::INDEX_TYPECHANGE) {
+        'T'
+    } else {
+        ' '
+    };
+
+    let worktree_char = if status.contains(git2::Status::WT_NEW) {
+        'A'
+    } else if status.contains(git2::Status::WT_MODIFIED) {
+        'M'
+    } else if status.contains(git2::Status::WT_DELETED) {
+        'D'
+    } else if status.contains(git2::Status::WT_RENAMED) {
+        'R'
+    } else if status.contains(git2::Status::WT_TYPECHANGE) {
+        'T'
+    } else {
+        ' '
+    };
+
+    format!("{}{}", index_char, worktree_char)
+}
+
+/// Format a git2 commit time the way `git log --format=%ci` would, so the
+/// first-19-characters date parsing elsewhere in the app keeps working.
+fn format_commit_time(time: git2::Time) -> String {
+    let offset = chrono::FixedOffset::east_opt(time.offset_minutes() * 60)
+        .unwrap_or_else(|| chrono::FixedOffset::east_opt(0).unwrap());
+    let datetime = chrono::DateTime::from_timestamp(time.seconds(), 0)
+        .unwrap_or_default()
+        .with_timezone(&offset);
+    datetime.format("%Y-%m-%d %H:%M:%S %z").to_string()
+}
+
+impl GitBackend for Git2Backend {
+    fn current_branch(&self, repo_path: &str) -> Result<String, String> {
+        let repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let head = match repo.head() {
+            Ok(head) => head,
+            Err(_) => return Ok("unknown".to_string()),
+        };
+        Ok(head.shorthand().unwrap_or("unknown").to_string())
+    }
+
+    fn status_entries(&self, repo_path: &str) -> Result<Vec<StatusEntry>, String> {
+        let repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true);
+        let statuses = repo.statuses(Some(&mut options)).map_err(|e| e.to_string())?;
+
+        Ok(statuses
+            .iter()
+            .filter_map(|entry| {
+                let path = entry.path()?.to_string();
+                Some(StatusEntry {
+                    status_code: status_code_chars(entry.status()),
+                    path,
+                })
+            })
+            .collect())
+    }
+
+    fn last_commit(&self, repo_path: &str) -> Result<Option<LastCommit>, String> {
+        let repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let head = match repo.head() {
+            Ok(head) => head,
+            Err(_) => return Ok(None),
+        };
+        let commit = match head.peel_to_commit() {
+            Ok(commit) => commit,
+            Err(_) => return Ok(None),
+        };
+
+        Ok(Some(LastCommit {
+            message: commit.summary().unwrap_or("").to_string(),
+            date: format_commit_time(commit.time()),
+        }))
+    }
+
+    fn operation_state(&self, repo_path: &str) -> Option<GitOperationState> {
+        detect_operation_state(repo_path)
+    }
+
+    fn conflicted_paths(&self, repo_path: &str) -> Result<Vec<String>, String> {
+        let repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let mut options = git2::StatusOptions::new();
+        options.include_untracked(true).recurse_untracked_dirs(true);
+        let statuses = repo.statuses(Some(&mut options)).map_err(|e| e.to_string())?;
+
+        Ok(statuses
+            .iter()
+            .filter(|entry| entry.status().contains(git2::Status::CONFLICTED))
+            .filter_map(|entry| entry.path().map(|p| p.to_string()))
+            .collect())
+    }
+
+    fn commit_timestamps_by_author(&self, repo_path: &str) -> Result<HashMap<String, Vec<i64>>, String> {
+        let repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
+        if revwalk.push_head().is_err() {
+            return Ok(HashMap::new());
+        }
+
+        let mut commits_by_author: HashMap<String, Vec<i64>> = HashMap::new();
+        for oid in revwalk {
+            let Ok(oid) = oid else { continue };
+            let Ok(commit) = repo.find_commit(oid) else { continue };
+            let author = commit.author();
+            let email = author.email().unwrap_or("unknown").to_string();
+            commits_by_author.entry(email).or_default().push(commit.time().seconds());
+        }
+
+        Ok(commits_by_author)
+    }
+
+    fn added_lines(&self, repo_path: &str, file_path: &str) -> Result<Vec<String>, String> {
+        let repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let head_tree = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_tree().ok());
+
+        let mut options = git2::DiffOptions::new();
+        options.pathspec(file_path);
+
+        let diff = repo
+            .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut options))
+            .map_err(|e| e.to_string())?;
+
+        let mut added = Vec::new();
+        diff.print(git2::DiffFormat::Patch, |_, _, line| {
+            if line.origin() == '+'
+                && let Ok(content) = std::str::from_utf8(line.content())
+            {
+                added.push(content.trim_end_matches('\n').to_string());
+            }
+            true
+        })
+        .map_err(|e| e.to_string())?;
+
+        Ok(added)
+    }
+
+    fn staged_changes(&self, repo_path: &str) -> Result<Vec<(String, String)>, String> {
+        let repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let head_tree = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+        let index = repo.index().map_err(|e| e.to_string())?;
+        let diff = repo
+            .diff_tree_to_index(head_tree.as_ref(), Some(&index), None)
+            .map_err(|e| e.to_string())?;
+
+        Ok(diff
+            .deltas()
+            .filter_map(|delta| {
+                let status = match delta.status() {
+                    git2::Delta::Added => "A",
+                    git2::Delta::Deleted => "D",
+                    git2::Delta::Renamed => "R",
+                    git2::Delta::Copied => "C",
+                    git2::Delta::Typechange => "T",
+                    _ => "M",
+                };
+                let path = delta.new_file().path()?.to_str()?.to_string();
+                Some((status.to_string(), path))
+            })
+            .collect())
+    }
+
+    fn stage_all(&self, repo_path: &str, dry_run: bool) -> Result<String, String> {
+        if dry_run {
+            let count = self.status_entries(repo_path)?.len();
+            return Ok(format!("[dry run] Would stage {} changed file(s).", count));
+        }
+        let repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let mut index = repo.index().map_err(|e| e.to_string())?;
+        index
+            .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+            .map_err(|e| e.to_string())?;
+        // `add_all` only stages new/modified paths; it leaves a path removed
+        // from the working tree still present in the index. `update_all`
+        // covers that case, matching `git add -A` (and `ProcessBackend`,
+        // which shells out to the real thing).
+        index.update_all(["*"].iter(), None).map_err(|e| e.to_string())?;
+        index.write().map_err(|e| e.to_string())?;
+        Ok(String::new())
+    }
+
+    fn commit(&self, repo_path: &str, message: &str, dry_run: bool) -> Result<String, String> {
+        if dry_run {
+            return Ok(format!("[dry run] Would commit staged changes with message: \"{}\"", message));
+        }
+        let repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let mut index = repo.index().map_err(|e| e.to_string())?;
+        let tree_id = index.write_tree().map_err(|e| e.to_string())?;
+        let tree = repo.find_tree(tree_id).map_err(|e| e.to_string())?;
+        let signature = repo.signature().map_err(|e| e.to_string())?;
+        let parent_commit = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent_commit.iter().collect();
+
+        let commit_oid = repo
+            .commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+            .map_err(|e| e.to_string())?;
+
+        let branch = self.current_branch(repo_path).unwrap_or_else(|_| "HEAD".to_string());
+        let short_oid = commit_oid.to_string().chars().take(7).collect::<String>();
+        Ok(format!("[{} {}] {}", branch, short_oid, message))
+    }
+
+    fn create_branch(&self, repo_path: &str, branch_name: &str, dry_run: bool) -> Result<String, String> {
+        if dry_run {
+            return Ok(format!("[dry run] Would create and check out branch \"{}\".", branch_name));
+        }
+        let repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let head_commit = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|e| e.to_string())?;
+        repo.branch(branch_name, &head_commit, false).map_err(|e| e.to_string())?;
+        repo.set_head(&format!("refs/heads/{}", branch_name)).map_err(|e| e.to_string())?;
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().safe()))
+            .map_err(|e| e.to_string())?;
+        Ok(format!("Switched to a new branch '{}'", branch_name))
+    }
+
+    fn wip_commit(&self, repo_path: &str, message: &str, dry_run: bool) -> Result<String, String> {
+        if dry_run {
+            let count = self.status_entries(repo_path)?.len();
+            return Ok(format!(
+                "[dry run] Would stage {} changed file(s) and commit as \"{}\".",
+                count, message
+            ));
+        }
+        self.stage_all(repo_path, false)?;
+        self.commit(repo_path, message, false)
+    }
+
+    fn panic_backup(&self, repo_path: &str, dry_run: bool) -> Result<String, String> {
+        let backup_name = format!("panic-backup-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        if dry_run {
+            let count = self.status_entries(repo_path)?.len();
+            return Ok(format!(
+                "[dry run] Would stage {} changed file(s) and stash them as \"Panic backup {}\".",
+                count, backup_name
+            ));
+        }
+        self.stage_all(repo_path, false)?;
+        let mut repo = git2::Repository::open(repo_path).map_err(|e| e.to_string())?;
+        let signature = repo.signature().map_err(|e| e.to_string())?;
+        repo.stash_save(&signature, &format!("Panic backup {}", backup_name), None)
+            .map_err(|e| e.to_string())?;
+        Ok("📎 Created panic backup stash. Use 'git stash list' to see it. Breathe. It's going to be okay. 🫂".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_repo(name: &str) -> std::path::PathBuf {
+        static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!("git_backend_test_{}_{}_{}", name, std::process::id(), id));
+        std::fs::create_dir_all(&dir).unwrap();
+        let repo = git2::Repository::init(&dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test User").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        dir
+    }
+
+    fn commit_file(repo_path: &std::path::Path, name: &str, content: &str) {
+        std::fs::write(repo_path.join(name), content).unwrap();
+        let backend = Git2Backend;
+        backend.stage_all(&repo_path.to_string_lossy(), false).unwrap();
+        backend.commit(&repo_path.to_string_lossy(), &format!("add {}", name), false).unwrap();
+    }
+
+    #[test]
+    fn status_code_chars_reports_untracked_as_question_marks() {
+        let status = git2::Status::WT_NEW;
+        assert_eq!(status_code_chars(status), "??");
+    }
+
+    #[test]
+    fn status_code_chars_reports_staged_modification() {
+        let status = git2::Status::INDEX_MODIFIED;
+        assert_eq!(status_code_chars(status), "M ");
+    }
+
+    #[test]
+    fn git2_backend_starts_with_no_commits_and_no_status() {
+        let dir = test_repo("empty");
+        let backend = Git2Backend;
+        let repo_path = dir.to_string_lossy().to_string();
+
+        assert!(backend.last_commit(&repo_path).unwrap().is_none());
+        assert!(backend.status_entries(&repo_path).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn stage_all_then_commit_records_a_commit() {
+        let dir = test_repo("commit");
+        let repo_path = dir.to_string_lossy().to_string();
+        std::fs::write(dir.join("a.txt"), "hello").unwrap();
+
+        let backend = Git2Backend;
+        backend.stage_all(&repo_path, false).unwrap();
+        backend.commit(&repo_path, "add a.txt", false).unwrap();
+
+        let last = backend.last_commit(&repo_path).unwrap().unwrap();
+        assert_eq!(last.message, "add a.txt");
+        assert!(backend.status_entries(&repo_path).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn stage_all_is_a_noop_in_dry_run() {
+        let dir = test_repo("dry_run");
+        let repo_path = dir.to_string_lossy().to_string();
+        std::fs::write(dir.join("a.txt"), "hello").unwrap();
+
+        let backend = Git2Backend;
+        let message = backend.stage_all(&repo_path, true).unwrap();
+        assert!(message.starts_with("[dry run]"));
+        assert!(!backend.status_entries(&repo_path).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn stage_all_stages_deletions() {
+        let dir = test_repo("deletion");
+        let repo_path = dir.to_string_lossy().to_string();
+        commit_file(&dir, "a.txt", "hello");
+        std::fs::remove_file(dir.join("a.txt")).unwrap();
+
+        let backend = Git2Backend;
+        backend.stage_all(&repo_path, false).unwrap();
+
+        let staged = backend.staged_changes(&repo_path).unwrap();
+        assert_eq!(staged, vec![("D".to_string(), "a.txt".to_string())]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn wip_commit_stages_and_commits_in_one_call() {
+        let dir = test_repo("wip");
+        let repo_path = dir.to_string_lossy().to_string();
+        std::fs::write(dir.join("scratch.txt"), "wip").unwrap();
+
+        let backend = Git2Backend;
+        backend.wip_commit(&repo_path, "WIP: checkpoint", false).unwrap();
+
+        let last = backend.last_commit(&repo_path).unwrap().unwrap();
+        assert_eq!(last.message, "WIP: checkpoint");
+        assert!(backend.status_entries(&repo_path).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn create_branch_switches_current_branch() {
+        let dir = test_repo("branch");
+        let repo_path = dir.to_string_lossy().to_string();
+        commit_file(&dir, "a.txt", "hello");
+
+        let backend = Git2Backend;
+        backend.create_branch(&repo_path, "feature/new-thing", false).unwrap();
+
+        assert_eq!(backend.current_branch(&repo_path).unwrap(), "feature/new-thing");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn panic_backup_stashes_changes_and_clears_status() {
+        let dir = test_repo("panic");
+        let repo_path = dir.to_string_lossy().to_string();
+        commit_file(&dir, "a.txt", "hello");
+        std::fs::write(dir.join("a.txt"), "changed").unwrap();
+
+        let backend = Git2Backend;
+        backend.panic_backup(&repo_path, false).unwrap();
+
+        assert!(backend.status_entries(&repo_path).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn conflicted_paths_finds_merge_conflicts() {
+        let dir = test_repo("conflict");
+        let repo_path = dir.to_string_lossy().to_string();
+        commit_file(&dir, "a.txt", "base\n");
+
+        let backend = Git2Backend;
+        let base_branch = backend.current_branch(&repo_path).unwrap();
+
+        backend.create_branch(&repo_path, "other", false).unwrap();
+        std::fs::write(dir.join("a.txt"), "their change\n").unwrap();
+        backend.stage_all(&repo_path, false).unwrap();
+        backend.commit(&repo_path, "their change", false).unwrap();
+
+        let repo = git2::Repository::open(&dir).unwrap();
+        repo.set_head(&format!("refs/heads/{}", base_branch)).unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+        std::fs::write(dir.join("a.txt"), "our change\n").unwrap();
+        backend.stage_all(&repo_path, false).unwrap();
+        backend.commit(&repo_path, "our change", false).unwrap();
+
+        let their_commit = repo
+            .find_branch("other", git2::BranchType::Local)
+            .unwrap()
+            .get()
+            .peel_to_commit()
+            .unwrap();
+        let annotated = repo.find_annotated_commit(their_commit.id()).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+
+        let conflicted = backend.conflicted_paths(&repo_path).unwrap();
+        assert_eq!(conflicted, vec!["a.txt".to_string()]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn commit_timestamps_by_author_groups_by_email() {
+        let dir = test_repo("timestamps");
+        let repo_path = dir.to_string_lossy().to_string();
+        commit_file(&dir, "a.txt", "one");
+        commit_file(&dir, "b.txt", "two");
+
+        let backend = Git2Backend;
+        let by_author = backend.commit_timestamps_by_author(&repo_path).unwrap();
+
+        assert_eq!(by_author.get("test@example.com").map(Vec::len), Some(2));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn operation_state_is_none_outside_any_git_operation() {
+        let dir = test_repo("opstate");
+        assert!(detect_operation_state(&dir.to_string_lossy()).is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,26 +1,148 @@
 // Python subprocess handler for Tauri commands
-use std::process::{Command, Stdio};
-use std::io::Write;
+use serde::Deserialize;
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
+use tokio::sync::{oneshot, Mutex};
 
+/// What a `call_python` caller is waiting to hear back: either the real
+/// JSON-RPC response, or a signal that the worker died before (or while)
+/// handling this call, in which case `call_python` respawns it and retries
+/// once.
+enum PythonOutcome {
+    Response(Result<Value, String>),
+    WorkerDied,
+}
+
+/// In-flight requests keyed by the `id` each was sent with, so the reader
+/// task can route an out-of-order response back to the right caller.
+type PendingMap = Arc<Mutex<HashMap<u64, oneshot::Sender<PythonOutcome>>>>;
+
+#[derive(Deserialize)]
+struct ResponseFrame {
+    id: u64,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+struct ChildHandle {
+    child: Child,
+    stdin: ChildStdin,
+}
+
+/// Speaks newline-delimited JSON-RPC to a single long-lived
+/// `python3 -m md_scanner.tauri_bridge` child instead of spawning a fresh
+/// interpreter per call. Each request carries an incrementing `id`; a
+/// background reader task owns the worker's stdout and routes each
+/// response back to the right `call_python` caller via a `oneshot`
+/// channel, so concurrent calls don't have to take turns.
+///
+/// When an [`AppHandle`] is attached via [`PythonBridge::attach_app_handle`],
+/// each worker's stderr is additionally streamed line-by-line to the
+/// webview as it's produced, so a long-running Python job reads as a live
+/// console instead of a frozen progress bar, and a terminal event reports
+/// the exit code once the worker process ends.
 pub struct PythonBridge {
     python_path: String,
+    next_id: AtomicU64,
+    pending: PendingMap,
+    child: Arc<Mutex<Option<ChildHandle>>>,
+    app_handle: Mutex<Option<AppHandle>>,
+    /// The `task_store` task id that the worker is currently making a
+    /// `call_python_for_task` call on behalf of, if any. Scopes
+    /// `cancel_for_task` so cancelling task A can't kill a worker that's
+    /// actually mid-call for unrelated task B.
+    active_task: Mutex<Option<String>>,
 }
 
 impl PythonBridge {
     pub fn new() -> Self {
         let python_path = std::env::var("PYTHON_PATH")
             .unwrap_or_else(|_| "python3".to_string());
-        Self { python_path }
+        PythonBridge {
+            python_path,
+            next_id: AtomicU64::new(1),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            child: Arc::new(Mutex::new(None)),
+            app_handle: Mutex::new(None),
+            active_task: Mutex::new(None),
+        }
     }
 
-    pub async fn call_python(&self, method: &str, args: Value) -> Result<Value, String> {
-        let payload = json!({
-            "method": method,
-            "args": args
-        });
+    /// Attach the app handle used to stream worker stderr/exit events to the
+    /// webview. Calls made before this is set simply don't stream.
+    pub async fn attach_app_handle(&self, app: AppHandle) {
+        *self.app_handle.lock().await = Some(app);
+    }
+
+    /// Like `call_python`, but records `task_id` as the worker's
+    /// `active_task` for the call's duration, so `cancel_for_task(task_id)`
+    /// can find and kill it while it's in flight.
+    pub async fn call_python_for_task(&self, task_id: &str, method: &str, args: Value) -> Result<Value, String> {
+        *self.active_task.lock().await = Some(task_id.to_string());
+        let result = self.call_python(method, args).await;
+        *self.active_task.lock().await = None;
+        result
+    }
+
+    /// Cancel the worker on behalf of `task_id`: kills the child process
+    /// and fails every in-flight `call_python` caller only if this
+    /// bridge's `active_task` is currently `task_id`. Returns `Ok(false)`
+    /// without touching the worker if some other (or no) task is running,
+    /// so an unrelated caller's `cancel_task` can't kill a task it doesn't
+    /// own.
+    pub async fn cancel_for_task(&self, task_id: &str) -> Result<bool, String> {
+        {
+            let active = self.active_task.lock().await;
+            if active.as_deref() != Some(task_id) {
+                return Ok(false);
+            }
+        }
+
+        self.cancel_current().await?;
+        *self.active_task.lock().await = None;
+        Ok(true)
+    }
+
+    /// Abort whatever the worker is doing right now: kill its process and
+    /// fail every in-flight `call_python` caller instead of leaving them to
+    /// await a response that will never arrive. `call_python` transparently
+    /// respawns a fresh worker on its next call, same as a crash.
+    pub async fn cancel_current(&self) -> Result<(), String> {
+        if let Some(mut handle) = self.child.lock().await.take() {
+            handle.child.start_kill().map_err(|e| format!("Failed to kill Python worker: {}", e))?;
+        }
 
+        let mut pending = self.pending.lock().await;
+        for (_, tx) in pending.drain() {
+            let _ = tx.send(PythonOutcome::WorkerDied);
+        }
+
+        Ok(())
+    }
+
+    /// Make sure a worker is running, spawning one if there isn't one yet
+    /// or the last one has exited.
+    async fn ensure_worker(&self) -> Result<(), String> {
+        let mut slot = self.child.lock().await;
+        let alive = match slot.as_mut() {
+            Some(handle) => handle.child.try_wait().ok().flatten().is_none(),
+            None => false,
+        };
+        if !alive {
+            *slot = Some(self.spawn_worker().await?);
+        }
+        Ok(())
+    }
+
+    async fn spawn_worker(&self) -> Result<ChildHandle, String> {
         let mut child = Command::new(&self.python_path)
             .arg("-m")
             .arg("md_scanner.tauri_bridge")
@@ -30,49 +152,194 @@ impl PythonBridge {
             .spawn()
             .map_err(|e| format!("Failed to spawn Python process: {}", e))?;
 
-        {
-            let mut stdin = child.stdin.take()
-                .ok_or_else(|| "Failed to open stdin".to_string())?;
-            
-            stdin.write_all(payload.to_string().as_bytes())
-                .map_err(|e| format!("Failed to write to stdin: {}", e))?;
+        let stdin = child.stdin.take().ok_or_else(|| "Failed to open stdin".to_string())?;
+        let stdout = child.stdout.take().ok_or_else(|| "Failed to open stdout".to_string())?;
+        let stderr = child.stderr.take().ok_or_else(|| "Failed to open stderr".to_string())?;
+
+        let pending = Arc::clone(&self.pending);
+        tokio::spawn(async move { read_responses(stdout, pending).await });
+
+        if let Some(app) = self.app_handle.lock().await.clone() {
+            let child_slot = Arc::clone(&self.child);
+            tokio::spawn(async move { stream_stderr(app, child_slot, stderr).await });
         }
 
-        let output = child.wait_with_output()
-            .map_err(|e| format!("Failed to wait for Python process: {}", e))?;
+        Ok(ChildHandle { child, stdin })
+    }
+
+    /// Send one `{"id", "method", "args"}` request over the worker's
+    /// persistent stdin and await the matching response frame, respawning
+    /// the worker and retrying exactly once if it died before (or while)
+    /// handling this call.
+    pub async fn call_python(&self, method: &str, args: Value) -> Result<Value, String> {
+        match self.try_call(method, &args).await {
+            PythonOutcome::Response(result) => result,
+            PythonOutcome::WorkerDied => match self.try_call(method, &args).await {
+                PythonOutcome::Response(result) => result,
+                PythonOutcome::WorkerDied => Err("Python worker repeatedly died before responding".to_string()),
+            },
+        }
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Python error: {}", stderr));
+    async fn try_call(&self, method: &str, args: &Value) -> PythonOutcome {
+        if let Err(e) = self.ensure_worker().await {
+            return PythonOutcome::Response(Err(e));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        serde_json::from_str(&stdout)
-            .map_err(|e| format!("Failed to parse Python response: {}", e))
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+
+        let mut line = json!({ "id": id, "method": method, "args": args }).to_string();
+        line.push('\n');
+
+        let write_result = {
+            let mut slot = self.child.lock().await;
+            match slot.as_mut() {
+                Some(handle) => handle.stdin.write_all(line.as_bytes()).await,
+                None => return PythonOutcome::WorkerDied,
+            }
+        };
+
+        if write_result.is_err() {
+            self.pending.lock().await.remove(&id);
+            *self.child.lock().await = None;
+            return PythonOutcome::WorkerDied;
+        }
+
+        match rx.await {
+            Ok(outcome) => outcome,
+            Err(_) => PythonOutcome::WorkerDied,
+        }
+    }
+}
+
+impl Default for PythonBridge {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+/// The process-wide `PythonBridge`, shared so `commands::cancel_task` can
+/// reach the same worker a `call_python_for_task` call is blocked on,
+/// rather than each call site holding its own bridge (and its own,
+/// uncancelable, child process).
+static SHARED_BRIDGE: once_cell::sync::Lazy<PythonBridge> = once_cell::sync::Lazy::new(PythonBridge::new);
+
+pub fn shared() -> &'static PythonBridge {
+    &SHARED_BRIDGE
+}
+
+/// Background task owning a worker's stdout: reads one JSON-RPC response
+/// frame per line and routes it to the caller waiting on its `id`. Once the
+/// worker exits (stdout closes), every still-pending caller is told it died
+/// instead of being left to await forever.
+async fn read_responses(stdout: ChildStdout, pending: PendingMap) {
+    let mut lines = BufReader::new(stdout).lines();
+    loop {
+        match lines.next_line().await {
+            Ok(Some(line)) => {
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let frame: ResponseFrame = match serde_json::from_str(&line) {
+                    Ok(frame) => frame,
+                    Err(e) => {
+                        eprintln!("[PYTHON_BRIDGE] Malformed response frame: {}", e);
+                        continue;
+                    }
+                };
+
+                let mut pending = pending.lock().await;
+                if let Some(tx) = pending.remove(&frame.id) {
+                    let result = match (frame.result, frame.error) {
+                        (Some(value), _) => Ok(value),
+                        (None, Some(error)) => Err(error),
+                        (None, None) => Err("Python response had neither result nor error".to_string()),
+                    };
+                    let _ = tx.send(PythonOutcome::Response(result));
+                }
+            }
+            _ => break,
+        }
+    }
+
+    let mut pending = pending.lock().await;
+    for (_, tx) in pending.drain() {
+        let _ = tx.send(PythonOutcome::WorkerDied);
+    }
+}
+
+/// Background task owning a worker's stderr: forwards each line to the
+/// webview as a `python-bridge-output` event as it's produced, instead of
+/// only surfacing stderr once a call happens to fail. Once stderr closes
+/// (the worker has exited), emits a terminal event with its exit code.
+async fn stream_stderr(app: AppHandle, child: Arc<Mutex<Option<ChildHandle>>>, stderr: ChildStderr) {
+    let emit_output = channel::create_output_emitter(&app, "python-bridge-output");
+    let mut lines = BufReader::new(stderr).lines();
+    loop {
+        match lines.next_line().await {
+            Ok(Some(line)) => emit_output("stderr", &line),
+            _ => break,
+        }
+    }
+
+    let code = match child.lock().await.as_mut() {
+        Some(handle) => match handle.child.try_wait() {
+            Ok(Some(status)) => status.code(),
+            _ => None,
+        },
+        None => None,
+    };
+
+    use tauri::Emitter;
+    let _ = app.emit("python-bridge-output", json!({ "code": code }));
+}
+
 pub mod channel {
-    pub fn create_progress_emitter(app: &tauri::AppHandle, event_name: &str) 
-        -> impl Fn(usize, usize) + Send + Sync 
+    pub fn create_progress_emitter(app: &tauri::AppHandle, event_name: &str)
+        -> impl Fn(usize, usize) + Send + Sync
     {
         use tauri::Emitter;
         let app = app.clone();
         let event_name = event_name.to_string();
-        
+
         move |current: usize, total: usize| {
             let percent = if total > 0 {
                 (current as f32 / total as f32) * 100.0
             } else {
                 0.0
             };
-            
+
             let payload = serde_json::json!({
                 "current": current,
                 "total": total,
                 "percent": percent
             });
-            
+
+            let _ = app.emit(&event_name, payload);
+        }
+    }
+
+    /// Emits one `{"stream","line","ts"}` event per line of a subprocess's
+    /// stdout/stderr as it's produced, so a long-running job reads as a
+    /// live console instead of going quiet until it exits.
+    pub fn create_output_emitter(app: &tauri::AppHandle, event_name: &str)
+        -> impl Fn(&str, &str) + Send + Sync
+    {
+        use chrono::Local;
+        use tauri::Emitter;
+        let app = app.clone();
+        let event_name = event_name.to_string();
+
+        move |stream: &str, line: &str| {
+            let payload = serde_json::json!({
+                "stream": stream,
+                "line": line,
+                "ts": Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            });
+
             let _ = app.emit(&event_name, payload);
         }
     }
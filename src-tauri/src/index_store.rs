@@ -0,0 +1,506 @@
+// Pluggable storage backend for the scanned file list and embedding
+// vectors. `generate_embeddings` used to re-serialize and rewrite the
+// entire `embeddings.json` on every checkpoint (and `scan_directory` did
+// the same for `index.json`), which is O(n) per save and loses whatever
+// wasn't flushed yet if the process dies mid-write. `IndexStore` lets
+// callers upsert one file/embedding row at a time instead.
+//
+// `JsonIndexStore` keeps the original whole-file `index.json`/
+// `embeddings.json` behavior as the default fallback, so existing indexes
+// still load unchanged. `SqliteIndexStore` backs the same trait with real
+// incremental upserts (`INSERT ... ON CONFLICT DO UPDATE`) against a local
+// `index.db`, storing each vector as a little-endian f32 BLOB, and never
+// has to hold the whole embedding set in memory to save one row.
+
+use crate::commands::{EmbeddingsData, FileEmbedding, FileEntry, IndexData};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IndexStoreStats {
+    pub file_count: usize,
+    pub embedding_count: usize,
+}
+
+/// Backend-agnostic API for the scanned file list and embedding vectors.
+/// Call sites should depend on this trait instead of reading/writing
+/// `index.json`/`embeddings.json` directly, so the same `scan_directory` /
+/// `generate_embeddings` / `search` flows work against either backend.
+pub trait IndexStore: Send {
+    fn upsert_file(&self, file: &FileEntry) -> Result<(), String>;
+    fn get_files(&self) -> Result<Vec<FileEntry>, String>;
+    fn upsert_embedding(&self, embedding: &FileEmbedding) -> Result<(), String>;
+    fn get_embeddings(&self) -> Result<Vec<FileEmbedding>, String>;
+    fn stats(&self) -> Result<IndexStoreStats, String>;
+}
+
+/// Default backend: the original whole-file `index.json`/`embeddings.json`
+/// read-modify-write behavior. Every upsert still rewrites the whole file
+/// it touches, so a large index pays the same O(n)-per-save cost this
+/// trait exists to let `SqliteIndexStore` avoid; it's kept as the
+/// always-available fallback.
+pub struct JsonIndexStore {
+    index_file: PathBuf,
+    embeddings_file: PathBuf,
+}
+
+impl JsonIndexStore {
+    pub fn open(index_dir: &str) -> Result<Self, String> {
+        fs::create_dir_all(index_dir).map_err(|e| format!("Failed to create index directory: {}", e))?;
+        Ok(Self {
+            index_file: Path::new(index_dir).join("index.json"),
+            embeddings_file: Path::new(index_dir).join("embeddings.json"),
+        })
+    }
+
+    fn load_index(&self) -> IndexData {
+        fs::read_to_string(&self.index_file)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_else(|| IndexData { files: Vec::new(), scan_path: String::new(), created_at: String::new() })
+    }
+
+    fn save_index(&self, data: &IndexData) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(data).map_err(|e| format!("Failed to serialize index: {}", e))?;
+        fs::write(&self.index_file, json).map_err(|e| format!("Failed to write index file: {}", e))
+    }
+
+    fn load_embeddings(&self) -> EmbeddingsData {
+        fs::read_to_string(&self.embeddings_file)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_else(|| EmbeddingsData {
+                embeddings: Vec::new(),
+                model: String::new(),
+                created_at: String::new(),
+                provider: String::new(),
+                dimensions: 0,
+            })
+    }
+
+    fn save_embeddings(&self, data: &EmbeddingsData) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(data).map_err(|e| format!("Failed to serialize embeddings: {}", e))?;
+        fs::write(&self.embeddings_file, json).map_err(|e| format!("Failed to write embeddings file: {}", e))
+    }
+}
+
+impl IndexStore for JsonIndexStore {
+    fn upsert_file(&self, file: &FileEntry) -> Result<(), String> {
+        let mut data = self.load_index();
+        match data.files.iter_mut().find(|f| f.path == file.path) {
+            Some(existing) => *existing = file.clone(),
+            None => data.files.push(file.clone()),
+        }
+        self.save_index(&data)
+    }
+
+    fn get_files(&self) -> Result<Vec<FileEntry>, String> {
+        Ok(self.load_index().files)
+    }
+
+    fn upsert_embedding(&self, embedding: &FileEmbedding) -> Result<(), String> {
+        let mut data = self.load_embeddings();
+        match data
+            .embeddings
+            .iter_mut()
+            .find(|e| e.path == embedding.path && e.chunk_index == embedding.chunk_index)
+        {
+            Some(existing) => *existing = embedding.clone(),
+            None => data.embeddings.push(embedding.clone()),
+        }
+        self.save_embeddings(&data)
+    }
+
+    fn get_embeddings(&self) -> Result<Vec<FileEmbedding>, String> {
+        Ok(self.load_embeddings().embeddings)
+    }
+
+    fn stats(&self) -> Result<IndexStoreStats, String> {
+        Ok(IndexStoreStats {
+            file_count: self.load_index().files.len(),
+            embedding_count: self.load_embeddings().embeddings.len(),
+        })
+    }
+}
+
+/// SQLite-backed store. Files and embeddings live in their own tables keyed
+/// by path (embeddings additionally by `chunk_index`), so a single row can
+/// be upserted inside its own transaction instead of rewriting every other
+/// row along with it.
+pub struct SqliteIndexStore {
+    conn: Connection,
+}
+
+impl SqliteIndexStore {
+    pub fn open(index_dir: &str) -> Result<Self, String> {
+        fs::create_dir_all(index_dir).map_err(|e| format!("Failed to create index directory: {}", e))?;
+        let conn = Connection::open(Path::new(index_dir).join("index.db"))
+            .map_err(|e| format!("Failed to open index database: {}", e))?;
+
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS files (
+                path TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                modified TEXT NOT NULL,
+                extension TEXT NOT NULL,
+                record_id INTEGER,
+                parent_file TEXT
+            );
+            CREATE TABLE IF NOT EXISTS embeddings (
+                path TEXT NOT NULL,
+                chunk_index INTEGER NOT NULL,
+                content_hash TEXT NOT NULL,
+                start_byte INTEGER NOT NULL,
+                end_byte INTEGER NOT NULL,
+                vector BLOB NOT NULL,
+                PRIMARY KEY (path, chunk_index)
+            );
+            CREATE TABLE IF NOT EXISTS meta (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+            "#,
+        )
+        .map_err(|e| format!("Failed to create tables: {}", e))?;
+
+        Ok(Self { conn })
+    }
+}
+
+/// Encode a vector as a little-endian f32 BLOB for storage.
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(vector.len() * 4);
+    for v in vector {
+        bytes.extend_from_slice(&v.to_le_bytes());
+    }
+    bytes
+}
+
+/// Decode a little-endian f32 BLOB back into a vector.
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+impl IndexStore for SqliteIndexStore {
+    fn upsert_file(&self, file: &FileEntry) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT INTO files (path, name, size, modified, extension, record_id, parent_file)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT(path) DO UPDATE SET
+                    name = excluded.name,
+                    size = excluded.size,
+                    modified = excluded.modified,
+                    extension = excluded.extension,
+                    record_id = excluded.record_id,
+                    parent_file = excluded.parent_file",
+                params![
+                    file.path,
+                    file.name,
+                    file.size as i64,
+                    file.modified,
+                    file.extension,
+                    file.record_id.map(|v| v as i64),
+                    file.parent_file,
+                ],
+            )
+            .map_err(|e| format!("Failed to upsert file: {}", e))?;
+        Ok(())
+    }
+
+    fn get_files(&self) -> Result<Vec<FileEntry>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT path, name, size, modified, extension, record_id, parent_file FROM files")
+            .map_err(|e| format!("Failed to query files: {}", e))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(FileEntry {
+                    path: row.get(0)?,
+                    name: row.get(1)?,
+                    size: row.get::<_, i64>(2)? as u64,
+                    modified: row.get(3)?,
+                    extension: row.get(4)?,
+                    record_id: row.get::<_, Option<i64>>(5)?.map(|v| v as usize),
+                    parent_file: row.get(6)?,
+                })
+            })
+            .map_err(|e| format!("Failed to read files: {}", e))?;
+
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to read files: {}", e))
+    }
+
+    fn upsert_embedding(&self, embedding: &FileEmbedding) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT INTO embeddings (path, chunk_index, content_hash, start_byte, end_byte, vector)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(path, chunk_index) DO UPDATE SET
+                    content_hash = excluded.content_hash,
+                    start_byte = excluded.start_byte,
+                    end_byte = excluded.end_byte,
+                    vector = excluded.vector",
+                params![
+                    embedding.path,
+                    embedding.chunk_index as i64,
+                    embedding.content_hash,
+                    embedding.start_byte as i64,
+                    embedding.end_byte as i64,
+                    encode_vector(&embedding.embedding),
+                ],
+            )
+            .map_err(|e| format!("Failed to upsert embedding: {}", e))?;
+        Ok(())
+    }
+
+    fn get_embeddings(&self) -> Result<Vec<FileEmbedding>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT path, chunk_index, content_hash, start_byte, end_byte, vector FROM embeddings")
+            .map_err(|e| format!("Failed to query embeddings: {}", e))?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                let vector: Vec<u8> = row.get(5)?;
+                Ok(FileEmbedding {
+                    path: row.get(0)?,
+                    chunk_index: row.get::<_, i64>(1)? as usize,
+                    content_hash: row.get(2)?,
+                    start_byte: row.get::<_, i64>(3)? as usize,
+                    end_byte: row.get::<_, i64>(4)? as usize,
+                    embedding: decode_vector(&vector),
+                })
+            })
+            .map_err(|e| format!("Failed to read embeddings: {}", e))?;
+
+        rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("Failed to read embeddings: {}", e))
+    }
+
+    fn stats(&self) -> Result<IndexStoreStats, String> {
+        let file_count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM files", [], |r| r.get(0))
+            .map_err(|e| format!("Failed to count files: {}", e))?;
+        let embedding_count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM embeddings", [], |r| r.get(0))
+            .map_err(|e| format!("Failed to count embeddings: {}", e))?;
+
+        Ok(IndexStoreStats {
+            file_count: file_count as usize,
+            embedding_count: embedding_count as usize,
+        })
+    }
+}
+
+fn meta_connection(index_dir: &str) -> Result<Connection, String> {
+    fs::create_dir_all(index_dir).map_err(|e| format!("Failed to create index directory: {}", e))?;
+    let conn = Connection::open(Path::new(index_dir).join("index.db"))
+        .map_err(|e| format!("Failed to open index database: {}", e))?;
+    conn.execute("CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL)", [])
+        .map_err(|e| format!("Failed to create meta table: {}", e))?;
+    Ok(conn)
+}
+
+fn get_meta(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row("SELECT value FROM meta WHERE key = ?1", params![key], |r| r.get(0)).ok()
+}
+
+fn set_meta(conn: &Connection, key: &str, value: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )
+    .map_err(|e| format!("Failed to write meta: {}", e))?;
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StorageConfig {
+    backend: String,
+}
+
+fn storage_config_path(index_dir: &str) -> PathBuf {
+    Path::new(index_dir).join("storage_config.json")
+}
+
+/// Which backend is configured for this index: `"json"` (default) or
+/// `"sqlite"`. Absent (or unrecognized) falls back to `"json"`, so indexes
+/// created before this file existed keep behaving exactly as before.
+pub fn configured_backend(index_dir: &str) -> String {
+    fs::read_to_string(storage_config_path(index_dir))
+        .ok()
+        .and_then(|s| serde_json::from_str::<StorageConfig>(&s).ok())
+        .map(|c| c.backend)
+        .unwrap_or_else(|| "json".to_string())
+}
+
+/// Persist which backend this index should use going forward. Switching
+/// backends does not migrate existing data between them.
+pub fn set_configured_backend(index_dir: &str, backend: &str) -> Result<(), String> {
+    fs::create_dir_all(index_dir).map_err(|e| format!("Failed to create index directory: {}", e))?;
+    let config = StorageConfig { backend: backend.to_string() };
+    let json = serde_json::to_string_pretty(&config).map_err(|e| format!("Failed to serialize storage config: {}", e))?;
+    fs::write(storage_config_path(index_dir), json).map_err(|e| format!("Failed to write storage config: {}", e))
+}
+
+/// Open the `IndexStore` backend named by `backend` (`"json"` or `"sqlite"`).
+pub fn open_index_store(index_dir: &str, backend: &str) -> Result<Box<dyn IndexStore>, String> {
+    match backend {
+        "sqlite" => Ok(Box::new(SqliteIndexStore::open(index_dir)?)),
+        "json" => Ok(Box::new(JsonIndexStore::open(index_dir)?)),
+        other => Err(format!("Unknown storage backend: {}", other)),
+    }
+}
+
+/// Open whichever backend is configured for this index (`"json"` by default).
+pub fn open_index_store_for(index_dir: &str) -> Result<Box<dyn IndexStore>, String> {
+    open_index_store(index_dir, &configured_backend(index_dir))
+}
+
+/// Whether a scanned index already exists for this backend, without having
+/// to open it. Used by command handlers that previously checked
+/// `index.json.exists()` directly.
+pub fn index_exists(index_dir: &str, backend: &str) -> bool {
+    match backend {
+        "sqlite" => Path::new(index_dir).join("index.db").exists(),
+        _ => Path::new(index_dir).join("index.json").exists(),
+    }
+}
+
+/// Whether embeddings have been generated for this backend yet.
+pub fn embeddings_exist(index_dir: &str, backend: &str) -> bool {
+    match backend {
+        // `index.db` also exists after a scan alone, so count the rows.
+        "sqlite" => Path::new(index_dir).join("index.db").exists()
+            && SqliteIndexStore::open(index_dir)
+                .and_then(|store| store.stats())
+                .is_ok_and(|stats| stats.embedding_count > 0),
+        _ => Path::new(index_dir).join("embeddings.json").exists(),
+    }
+}
+
+/// Provider/model bookkeeping for the embedding cache. `IndexStore` itself
+/// only models rows, so this small side record tracks whether a cached
+/// embedding was produced by the currently configured provider, the same
+/// role `EmbeddingsData::provider`/`dimensions` played when every
+/// embedding lived in one `embeddings.json`.
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingsMeta {
+    pub provider: String,
+    pub model: String,
+    pub dimensions: usize,
+    pub created_at: String,
+}
+
+pub fn get_embeddings_meta(index_dir: &str, backend: &str) -> EmbeddingsMeta {
+    match backend {
+        "sqlite" => match meta_connection(index_dir) {
+            Ok(conn) => EmbeddingsMeta {
+                provider: get_meta(&conn, "embeddings_provider").unwrap_or_default(),
+                model: get_meta(&conn, "embeddings_model").unwrap_or_default(),
+                dimensions: get_meta(&conn, "embeddings_dimensions").and_then(|v| v.parse().ok()).unwrap_or(0),
+                created_at: get_meta(&conn, "embeddings_created_at").unwrap_or_default(),
+            },
+            Err(_) => EmbeddingsMeta::default(),
+        },
+        _ => fs::read_to_string(Path::new(index_dir).join("embeddings.json"))
+            .ok()
+            .and_then(|s| serde_json::from_str::<EmbeddingsData>(&s).ok())
+            .map(|d| EmbeddingsMeta {
+                provider: d.provider,
+                model: d.model,
+                dimensions: d.dimensions,
+                created_at: d.created_at,
+            })
+            .unwrap_or_default(),
+    }
+}
+
+pub fn set_embeddings_meta(index_dir: &str, backend: &str, meta: &EmbeddingsMeta) -> Result<(), String> {
+    match backend {
+        "sqlite" => {
+            let conn = meta_connection(index_dir)?;
+            set_meta(&conn, "embeddings_provider", &meta.provider)?;
+            set_meta(&conn, "embeddings_model", &meta.model)?;
+            set_meta(&conn, "embeddings_dimensions", &meta.dimensions.to_string())?;
+            set_meta(&conn, "embeddings_created_at", &meta.created_at)
+        }
+        _ => {
+            let path = Path::new(index_dir).join("embeddings.json");
+            let mut data = fs::read_to_string(&path)
+                .ok()
+                .and_then(|s| serde_json::from_str::<EmbeddingsData>(&s).ok())
+                .unwrap_or_else(|| EmbeddingsData {
+                    embeddings: Vec::new(),
+                    model: String::new(),
+                    created_at: String::new(),
+                    provider: String::new(),
+                    dimensions: 0,
+                });
+            data.provider = meta.provider.clone();
+            data.model = meta.model.clone();
+            data.dimensions = meta.dimensions;
+            data.created_at = meta.created_at.clone();
+            let json = serde_json::to_string_pretty(&data).map_err(|e| format!("Failed to serialize embeddings: {}", e))?;
+            fs::write(&path, json).map_err(|e| format!("Failed to write embeddings file: {}", e))
+        }
+    }
+}
+
+/// Clear all file rows before a fresh full-directory scan repopulates
+/// them, so rescanning after deletions actually drops the stale entries
+/// instead of leaving them forever — `IndexStore::upsert_file` alone is
+/// additive and has no notion of "no longer present". Call this only
+/// after the store itself has been opened at least once, so the backing
+/// table already exists.
+pub fn reset_files(index_dir: &str, backend: &str) -> Result<(), String> {
+    match backend {
+        "sqlite" => {
+            let conn = meta_connection(index_dir)?;
+            conn.execute("DELETE FROM files", []).map_err(|e| format!("Failed to clear files table: {}", e))?;
+            Ok(())
+        }
+        _ => {
+            let path = Path::new(index_dir).join("index.json");
+            let mut data = fs::read_to_string(&path)
+                .ok()
+                .and_then(|s| serde_json::from_str::<IndexData>(&s).ok())
+                .unwrap_or_else(|| IndexData { files: Vec::new(), scan_path: String::new(), created_at: String::new() });
+            data.files.clear();
+            let json = serde_json::to_string_pretty(&data).map_err(|e| format!("Failed to serialize index: {}", e))?;
+            fs::write(&path, json).map_err(|e| format!("Failed to write index file: {}", e))
+        }
+    }
+}
+
+/// Record the directory that was scanned and when — the two `index.json`
+/// fields `IndexStore` has no notion of, since it only models file rows.
+pub fn set_scan_metadata(index_dir: &str, backend: &str, scan_path: &str, created_at: &str) -> Result<(), String> {
+    match backend {
+        "sqlite" => {
+            let conn = meta_connection(index_dir)?;
+            set_meta(&conn, "scan_path", scan_path)?;
+            set_meta(&conn, "scan_created_at", created_at)
+        }
+        _ => {
+            let path = Path::new(index_dir).join("index.json");
+            let mut data = fs::read_to_string(&path)
+                .ok()
+                .and_then(|s| serde_json::from_str::<IndexData>(&s).ok())
+                .unwrap_or_else(|| IndexData { files: Vec::new(), scan_path: String::new(), created_at: String::new() });
+            data.scan_path = scan_path.to_string();
+            data.created_at = created_at.to_string();
+            let json = serde_json::to_string_pretty(&data).map_err(|e| format!("Failed to serialize index: {}", e))?;
+            fs::write(&path, json).map_err(|e| format!("Failed to write index file: {}", e))
+        }
+    }
+}
@@ -1,11 +1,23 @@
 // Tauri library exports
 pub mod commands;
+pub mod embedding_provider;
 pub mod git_assistant;
+pub mod git_backend;
 pub mod file_intelligence;
-pub mod pattern_database;
 pub mod file_watcher;
+pub mod watch_actions;
+pub mod task_store;
+pub mod task_queue;
+pub mod index_store;
+pub mod error;
+pub mod vector_index;
+pub mod embedding_template;
+pub mod storage_backend;
+pub mod observability;
+pub mod dump;
+pub mod vector_store;
+pub mod logger;
 
 #[cfg(test)]
 mod windows_deployment_tests;
-// pub mod handlers; // Not needed - using pure Rust
-// pub mod state;    // Not needed yet
+pub mod handlers;
@@ -0,0 +1,167 @@
+// Unified logging surface for the Rust side, following the same "own the
+// global `log` recorder, call sites just use the macros" shape
+// `observability.rs` uses for `metrics`: once `init_logging` installs this
+// as the process-wide `log::Log` implementation, any `log::info!`/`warn!`/
+// `error!` call in the crate routes through here. The existing output of
+// commands, file_watcher, git_assistant and the embedding pipeline is
+// still printed with `println!`/`eprintln!` and does not pass through it.
+//
+// Every record both appends to a day-rotated file under `<log_dir>/logs`
+// (a `fern`-style daily dispatch, hand-rolled here to avoid the extra
+// dependency) and, once an `AppHandle` is attached, emits a serialized
+// `{"level","target","message","ts"}` event so a frontend console can
+// show it live.
+
+use chrono::Local;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::OnceCell;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+fn level_to_u8(level: LevelFilter) -> u8 {
+    level as u8
+}
+
+fn u8_to_level(value: u8) -> LevelFilter {
+    match value {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+struct RollingFile {
+    log_dir: PathBuf,
+    current_date: String,
+    file: File,
+}
+
+impl RollingFile {
+    fn open(log_dir: &PathBuf) -> Result<Self, String> {
+        fs::create_dir_all(log_dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+        let current_date = Local::now().format("%Y-%m-%d").to_string();
+        let file = Self::open_for_date(log_dir, &current_date)?;
+        Ok(Self { log_dir: log_dir.clone(), current_date, file })
+    }
+
+    fn open_for_date(log_dir: &PathBuf, date: &str) -> Result<File, String> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_dir.join(format!("{}.log", date)))
+            .map_err(|e| format!("Failed to open log file: {}", e))
+    }
+
+    fn write_line(&mut self, line: &str) {
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        if today != self.current_date {
+            if let Ok(file) = Self::open_for_date(&self.log_dir, &today) {
+                self.file = file;
+                self.current_date = today;
+            }
+        }
+        let _ = writeln!(self.file, "{}", line);
+    }
+}
+
+/// Process-wide `log::Log` implementation. Installed once via
+/// `init_logging`; every `log::info!`/`warn!`/`error!` call site in the
+/// crate flows through [`LogBridge::log`] afterward.
+struct LogBridge {
+    level: AtomicU8,
+    app: Mutex<Option<AppHandle>>,
+    file: Mutex<Option<RollingFile>>,
+}
+
+static BRIDGE: OnceCell<LogBridge> = OnceCell::new();
+
+impl Log for LogBridge {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= u8_to_level(self.level.load(Ordering::Relaxed))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let ts = Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string();
+        let level = record.level();
+        let target = record.target().to_string();
+        let message = record.args().to_string();
+
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                file.write_line(&format!("[{}] {:<5} {}: {}", ts, level, target, message));
+            }
+        }
+
+        if let Ok(app) = self.app.lock() {
+            if let Some(app) = app.as_ref() {
+                let payload = serde_json::json!({
+                    "level": level.to_string(),
+                    "target": target,
+                    "message": message,
+                    "ts": ts,
+                });
+                let _ = app.emit("log-record", payload);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                let _ = file.file.flush();
+            }
+        }
+    }
+}
+
+/// Install the process-wide logger and start streaming `log` records into
+/// both `<log_dir>/logs/<date>.log` and, live, the webview via the
+/// `log-record` event. Call once per process: a later call returns an
+/// error and leaves the installed logger, and the `AppHandle` it streams
+/// to, unchanged; use [`set_level`] to adjust filtering afterward.
+pub fn init_logging(app: AppHandle, log_dir: &str) -> Result<(), String> {
+    let rolling = RollingFile::open(&PathBuf::from(log_dir).join("logs"))?;
+
+    let bridge = LogBridge {
+        level: AtomicU8::new(level_to_u8(LevelFilter::Info)),
+        app: Mutex::new(Some(app)),
+        file: Mutex::new(Some(rolling)),
+    };
+
+    BRIDGE.set(bridge).map_err(|_| "Logger already initialized".to_string())?;
+    log::set_logger(BRIDGE.get().unwrap()).map_err(|e| format!("Failed to install logger: {}", e))?;
+    log::set_max_level(LevelFilter::Trace);
+
+    Ok(())
+}
+
+/// Adjust the level filter at runtime (exposed as the `set_log_level`
+/// Tauri command), e.g. dropping to `Error` to quiet a noisy session
+/// without restarting the app.
+pub fn set_level(level: &str) -> Result<(), String> {
+    let parsed: LevelFilter = level.parse().map_err(|_| format!("Unrecognized log level: {}", level))?;
+    match BRIDGE.get() {
+        Some(bridge) => {
+            bridge.level.store(level_to_u8(parsed), Ordering::Relaxed);
+            Ok(())
+        }
+        None => Err("Logger not initialized".to_string()),
+    }
+}
+
+/// The level filter currently in effect, or `None` if `init_logging` has
+/// not been called yet.
+pub fn current_level() -> Option<Level> {
+    BRIDGE.get().and_then(|bridge| u8_to_level(bridge.level.load(Ordering::Relaxed)).to_level())
+}
@@ -1,20 +1,28 @@
 // Tauri app entry point
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use wayfinder_tauri::commands;
+use tauri::Manager;
+use wayfinder_tauri::{commands, logger};
 
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
-        .setup(|_app| {
-            // Initialize app state if needed
+        .setup(|app| {
+            let log_dir = app.path().app_data_dir().unwrap_or_else(|_| std::env::temp_dir());
+            if let Err(e) = logger::init_logging(app.handle().clone(), &log_dir.to_string_lossy()) {
+                eprintln!("[RUST] Failed to initialize logging: {}", e);
+            }
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::scan_directory,
             commands::generate_embeddings,
             commands::create_clusters,
+            commands::cluster_embeddings,
             commands::search,
+            commands::semantic_search,
+            #[cfg(feature = "pgvector")]
+            commands::search_embeddings,
             commands::get_clusters_summary,
             commands::get_timeline,
             commands::get_stats,
@@ -34,16 +42,44 @@ fn main() {
             commands::delete_duplicate_files,
             // File Intelligence commands
             commands::scan_for_documents,
+            commands::enqueue_scan,
             commands::get_organization_suggestions,
             commands::get_scan_statistics,
             commands::dismiss_suggestion,
+            commands::list_suggestion_rules,
+            commands::add_suggestion_rule,
+            commands::remove_suggestion_rule,
             // File Watcher commands
             commands::start_file_watcher,
             commands::stop_file_watcher,
+            commands::configure_watcher_filters,
             commands::get_file_events,
             commands::get_watcher_status,
             commands::validate_azure_config,
             commands::validate_all_azure_configs,
+            // Task store commands
+            commands::enqueue_task,
+            commands::list_tasks,
+            commands::get_task,
+            commands::cancel_task,
+            // Observability commands
+            commands::get_metrics,
+            commands::start_metrics_server,
+            // Logging commands
+            commands::set_log_level,
+            // Dump / restore commands
+            commands::create_dump,
+            commands::load_dump,
+            // Storage backend commands
+            commands::get_storage_backend,
+            commands::set_storage_backend,
+            // Vector index commands
+            commands::build_vector_index,
+            commands::query_vectors,
+            // Embedding template commands
+            commands::get_embedding_template,
+            commands::save_embedding_template,
+            commands::validate_embedding_template,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,67 @@
+// Prometheus-format observability, following the same `metrics` /
+// `metrics-exporter-prometheus` pattern pict-rs and garage use: call sites
+// elsewhere in the app record values directly through the `metrics::counter!`/
+// `metrics::histogram!` macros, and this module only owns installing the
+// global recorder and rendering/serving its current snapshot.
+
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use once_cell::sync::Lazy;
+use std::io::Write;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static HANDLE: Lazy<PrometheusHandle> = Lazy::new(|| {
+    PrometheusBuilder::new().install_recorder().expect("failed to install Prometheus recorder")
+});
+
+/// Registers the global recorder. Only does real work on the first call,
+/// since `HANDLE` is a `Lazy`; safe to call from every site that also calls
+/// `render()`/`start_scrape_server()` just to make sure it's installed.
+pub fn install() -> &'static PrometheusHandle {
+    &HANDLE
+}
+
+/// Render everything recorded so far in Prometheus text exposition format.
+pub fn render() -> String {
+    install().render()
+}
+
+static SERVER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Start a local HTTP server on `127.0.0.1:<port>` that serves the current
+/// exposition text on every connection, so an external Prometheus/Grafana
+/// can scrape it instead of polling the `get_metrics` Tauri command. A
+/// no-op if a scrape server is already running in this process.
+pub fn start_scrape_server(port: u16) -> Result<(), String> {
+    if SERVER_STARTED.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    let addr: SocketAddr = format!("127.0.0.1:{}", port).parse().map_err(|e| format!("Invalid metrics port: {}", e))?;
+    let listener = TcpListener::bind(addr).map_err(|e| {
+        // Nothing is running after a failed bind, so let a later call retry.
+        SERVER_STARTED.store(false, Ordering::SeqCst);
+        format!("Failed to bind metrics port {}: {}", port, e)
+    })?;
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            serve_one(stream);
+        }
+    });
+
+    Ok(())
+}
+
+/// Minimal scrape response: every connection gets the current exposition
+/// text regardless of request path/method, since this is a single-purpose
+/// `/metrics` endpoint rather than a general HTTP server.
+fn serve_one(mut stream: TcpStream) {
+    let body = render();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
@@ -0,0 +1,284 @@
+// Pluggable persistence for user preferences and the most recent document
+// scan. These used to live in `commands.rs` as `USER_PREFS`/`LAST_SCAN`
+// `Lazy<Mutex<...>>` globals (explicitly marked "will be replaced with
+// SQLite later"), which meant they were shared process-wide instead of
+// per-index and vanished on restart. `StorageBackend` replaces that with
+// real persistence, keyed by index directory so multiple indexes stay
+// isolated, following the same multi-backend-trait pattern `index_store.rs`
+// established for files/embeddings (and the pattern projects like
+// vaultwarden use for their own storage layer).
+//
+// The backend is selected at compile time via Cargo feature flags —
+// `sqlite` (default) or `postgres` — declared in `Cargo.toml` as:
+//   [features]
+//   default = ["sqlite"]
+//   sqlite = ["dep:rusqlite"]
+//   postgres = ["dep:postgres"]
+// At least one must be enabled; `open()` picks `sqlite` if both are.
+
+#[cfg(all(not(feature = "sqlite"), not(feature = "postgres")))]
+compile_error!("md-scanner requires a storage backend feature: \"sqlite\" (default) or \"postgres\"");
+
+use crate::file_intelligence::{DiscoveredDocument, UserPreferences};
+
+/// Backend-agnostic persistence for per-index user preferences and the
+/// most recent document scan. Call sites should depend on this trait
+/// instead of an in-memory global, so `dismiss_suggestion` /
+/// `scan_for_documents` / `get_organization_suggestions` survive restarts
+/// and don't leak state between differently-scanned index directories.
+pub trait StorageBackend: Send {
+    fn get_preferences(&self, index_dir: &str) -> Result<UserPreferences, String>;
+    fn save_preferences(&self, index_dir: &str, prefs: &UserPreferences) -> Result<(), String>;
+    fn get_last_scan(&self, index_dir: &str) -> Result<Vec<DiscoveredDocument>, String>;
+    fn save_last_scan(&self, index_dir: &str, documents: &[DiscoveredDocument]) -> Result<(), String>;
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite_backend {
+    use super::StorageBackend;
+    use crate::file_intelligence::{DiscoveredDocument, UserPreferences};
+    use rusqlite::{params, Connection};
+    use std::fs;
+    use std::path::Path;
+
+    /// SQLite-backed `StorageBackend`. `UserPreferences` and the scanned
+    /// document list are each arbitrarily-nested `serde` types (rules are a
+    /// recursive `RuleMatcher` tree, for instance), so rather than
+    /// normalizing them into relational tables this stores each as one
+    /// serialized JSON blob in a `key`/`value` table — the same role the
+    /// `meta` table plays in `index_store.rs`, and the same "opaque
+    /// JSON-as-an-internal-artifact" convention `vector_index.bin` uses.
+    pub struct SqliteStorageBackend;
+
+    impl SqliteStorageBackend {
+        fn connection(index_dir: &str) -> Result<Connection, String> {
+            fs::create_dir_all(index_dir).map_err(|e| format!("Failed to create index directory: {}", e))?;
+            let conn = Connection::open(Path::new(index_dir).join("app_state.db"))
+                .map_err(|e| format!("Failed to open app state database: {}", e))?;
+            conn.execute(
+                "CREATE TABLE IF NOT EXISTS kv (key TEXT PRIMARY KEY, value TEXT NOT NULL)",
+                [],
+            )
+            .map_err(|e| format!("Failed to create kv table: {}", e))?;
+            Ok(conn)
+        }
+
+        fn get(index_dir: &str, key: &str) -> Result<Option<String>, String> {
+            let conn = Self::connection(index_dir)?;
+            match conn.query_row("SELECT value FROM kv WHERE key = ?1", params![key], |r| r.get::<_, String>(0)) {
+                Ok(value) => Ok(Some(value)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(format!("Failed to read {}: {}", key, e)),
+            }
+        }
+
+        fn set(index_dir: &str, key: &str, value: &str) -> Result<(), String> {
+            let conn = Self::connection(index_dir)?;
+            conn.execute(
+                "INSERT INTO kv (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                params![key, value],
+            )
+            .map_err(|e| format!("Failed to save {}: {}", key, e))?;
+            Ok(())
+        }
+    }
+
+    impl StorageBackend for SqliteStorageBackend {
+        fn get_preferences(&self, index_dir: &str) -> Result<UserPreferences, String> {
+            match Self::get(index_dir, "preferences")? {
+                Some(json) => serde_json::from_str(&json).map_err(|e| format!("Failed to parse stored preferences: {}", e)),
+                None => Ok(UserPreferences::default()),
+            }
+        }
+
+        fn save_preferences(&self, index_dir: &str, prefs: &UserPreferences) -> Result<(), String> {
+            let json = serde_json::to_string(prefs).map_err(|e| format!("Failed to serialize preferences: {}", e))?;
+            Self::set(index_dir, "preferences", &json)
+        }
+
+        fn get_last_scan(&self, index_dir: &str) -> Result<Vec<DiscoveredDocument>, String> {
+            match Self::get(index_dir, "last_scan")? {
+                Some(json) => serde_json::from_str(&json).map_err(|e| format!("Failed to parse stored scan: {}", e)),
+                None => Ok(Vec::new()),
+            }
+        }
+
+        fn save_last_scan(&self, index_dir: &str, documents: &[DiscoveredDocument]) -> Result<(), String> {
+            let json = serde_json::to_string(documents).map_err(|e| format!("Failed to serialize scan: {}", e))?;
+            Self::set(index_dir, "last_scan", &json)
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod postgres_backend {
+    use super::StorageBackend;
+    use crate::file_intelligence::{DiscoveredDocument, UserPreferences};
+    use postgres::{Client, NoTls};
+    use std::sync::Mutex;
+
+    /// Postgres-backed `StorageBackend` for a shared/server deployment,
+    /// keyed the same way as `SqliteStorageBackend` (one JSON blob per
+    /// index directory per key) so both backends store identical data —
+    /// switching backends is a config change, not a migration of shape.
+    /// The connection string is read from `DATABASE_URL`, the convention
+    /// `sqlx`/`rusqlite`-adjacent Rust projects use for this.
+    pub struct PostgresStorageBackend {
+        client: Mutex<Client>,
+    }
+
+    impl PostgresStorageBackend {
+        pub fn connect() -> Result<Self, String> {
+            let url = std::env::var("DATABASE_URL")
+                .map_err(|_| "DATABASE_URL must be set to use the postgres storage backend".to_string())?;
+            let mut client = Client::connect(&url, NoTls).map_err(|e| format!("Failed to connect to Postgres: {}", e))?;
+            client
+                .batch_execute(
+                    "CREATE TABLE IF NOT EXISTS app_state_kv (
+                        index_dir TEXT NOT NULL,
+                        key TEXT NOT NULL,
+                        value TEXT NOT NULL,
+                        PRIMARY KEY (index_dir, key)
+                    )",
+                )
+                .map_err(|e| format!("Failed to create app_state_kv table: {}", e))?;
+            Ok(Self { client: Mutex::new(client) })
+        }
+
+        fn get(&self, index_dir: &str, key: &str) -> Result<Option<String>, String> {
+            let mut client = self.client.lock().map_err(|e| format!("Lock error: {}", e))?;
+            let rows = client
+                .query(
+                    "SELECT value FROM app_state_kv WHERE index_dir = $1 AND key = $2",
+                    &[&index_dir, &key],
+                )
+                .map_err(|e| format!("Failed to read {}: {}", key, e))?;
+            Ok(rows.first().map(|row| row.get::<_, String>(0)))
+        }
+
+        fn set(&self, index_dir: &str, key: &str, value: &str) -> Result<(), String> {
+            let mut client = self.client.lock().map_err(|e| format!("Lock error: {}", e))?;
+            client
+                .execute(
+                    "INSERT INTO app_state_kv (index_dir, key, value) VALUES ($1, $2, $3)
+                     ON CONFLICT (index_dir, key) DO UPDATE SET value = excluded.value",
+                    &[&index_dir, &key, &value],
+                )
+                .map_err(|e| format!("Failed to save {}: {}", key, e))?;
+            Ok(())
+        }
+    }
+
+    impl StorageBackend for PostgresStorageBackend {
+        fn get_preferences(&self, index_dir: &str) -> Result<UserPreferences, String> {
+            match self.get(index_dir, "preferences")? {
+                Some(json) => serde_json::from_str(&json).map_err(|e| format!("Failed to parse stored preferences: {}", e)),
+                None => Ok(UserPreferences::default()),
+            }
+        }
+
+        fn save_preferences(&self, index_dir: &str, prefs: &UserPreferences) -> Result<(), String> {
+            let json = serde_json::to_string(prefs).map_err(|e| format!("Failed to serialize preferences: {}", e))?;
+            self.set(index_dir, "preferences", &json)
+        }
+
+        fn get_last_scan(&self, index_dir: &str) -> Result<Vec<DiscoveredDocument>, String> {
+            match self.get(index_dir, "last_scan")? {
+                Some(json) => serde_json::from_str(&json).map_err(|e| format!("Failed to parse stored scan: {}", e)),
+                None => Ok(Vec::new()),
+            }
+        }
+
+        fn save_last_scan(&self, index_dir: &str, documents: &[DiscoveredDocument]) -> Result<(), String> {
+            let json = serde_json::to_string(documents).map_err(|e| format!("Failed to serialize scan: {}", e))?;
+            self.set(index_dir, "last_scan", &json)
+        }
+    }
+}
+
+/// Open the configured `StorageBackend`. `sqlite` wins if both features
+/// happen to be enabled at once, matching its role as the default.
+#[cfg(feature = "sqlite")]
+pub fn open() -> Result<Box<dyn StorageBackend>, String> {
+    Ok(Box::new(sqlite_backend::SqliteStorageBackend))
+}
+
+#[cfg(all(feature = "postgres", not(feature = "sqlite")))]
+pub fn open() -> Result<Box<dyn StorageBackend>, String> {
+    Ok(Box::new(postgres_backend::PostgresStorageBackend::connect()?))
+}
+
+#[cfg(test)]
+#[cfg(feature = "sqlite")]
+mod tests {
+    use super::*;
+    use crate::file_intelligence::DocumentType;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    // Each test gets its own directory so tests running in parallel don't
+    // trample each other's `app_state.db`.
+    fn test_dir() -> String {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        let dir = std::env::temp_dir().join(format!("md_storage_backend_test_{}_{}", std::process::id(), id));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.to_string_lossy().to_string()
+    }
+
+    fn sample_document() -> DiscoveredDocument {
+        DiscoveredDocument {
+            path: "/docs/report.pdf".to_string(),
+            name: "report.pdf".to_string(),
+            extension: "pdf".to_string(),
+            doc_type: DocumentType::PDF,
+            size_bytes: 1024,
+            modified: "2026-01-01".to_string(),
+            parent_dir: "/docs".to_string(),
+            depth: 1,
+            siblings_count: 3,
+            similar_siblings: 1,
+        }
+    }
+
+    #[test]
+    fn test_preferences_round_trip_and_default() {
+        let index_dir = test_dir();
+        let backend = open().unwrap();
+
+        let default_prefs = backend.get_preferences(&index_dir).unwrap();
+        assert!(default_prefs.dismissed_suggestions.is_empty());
+
+        let mut prefs = default_prefs;
+        prefs.dismissed_suggestions.push("/docs/old.txt".to_string());
+        backend.save_preferences(&index_dir, &prefs).unwrap();
+
+        let reloaded = backend.get_preferences(&index_dir).unwrap();
+        assert_eq!(reloaded.dismissed_suggestions, vec!["/docs/old.txt".to_string()]);
+    }
+
+    #[test]
+    fn test_last_scan_round_trip_and_default_empty() {
+        let index_dir = test_dir();
+        let backend = open().unwrap();
+
+        assert!(backend.get_last_scan(&index_dir).unwrap().is_empty());
+
+        let documents = vec![sample_document()];
+        backend.save_last_scan(&index_dir, &documents).unwrap();
+
+        let reloaded = backend.get_last_scan(&index_dir).unwrap();
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded[0].path, "/docs/report.pdf");
+    }
+
+    #[test]
+    fn test_isolated_by_index_dir() {
+        let dir_a = test_dir();
+        let dir_b = test_dir();
+        let backend = open().unwrap();
+
+        backend.save_last_scan(&dir_a, &[sample_document()]).unwrap();
+
+        assert!(backend.get_last_scan(&dir_b).unwrap().is_empty());
+    }
+}
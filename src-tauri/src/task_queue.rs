@@ -0,0 +1,44 @@
+// Background worker for jobs enqueued against `task_store`. A job (e.g. a
+// directory scan) is handed to this module as a plain closure instead of
+// running inline inside the Tauri command that enqueued it, so that command
+// can return the new `Task`'s id immediately instead of blocking the
+// frontend until the work finishes. A single worker thread drains an ordered,
+// bounded channel; once it's full, `enqueue` fails clearly instead of
+// growing without bound or blocking the caller.
+
+use once_cell::sync::Lazy;
+use std::sync::mpsc::{sync_channel, SyncSender, TrySendError};
+use std::sync::Mutex;
+use std::thread;
+
+/// Maximum number of jobs allowed to sit in the queue waiting for the
+/// worker thread. Chosen generously for a single-user desktop app; a caller
+/// hitting this almost certainly has a stuck or runaway job rather than a
+/// legitimate burst of work.
+const QUEUE_CAPACITY: usize = 64;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+static QUEUE: Lazy<Mutex<SyncSender<Job>>> = Lazy::new(|| {
+    let (tx, rx) = sync_channel::<Job>(QUEUE_CAPACITY);
+    thread::spawn(move || {
+        while let Ok(job) = rx.recv() {
+            job();
+        }
+    });
+    Mutex::new(tx)
+});
+
+/// Hand `job` off to the background worker thread, preserving the order in
+/// which jobs were enqueued. Returns an error instead of running `job` if
+/// the queue already holds `QUEUE_CAPACITY` jobs waiting on the worker.
+pub fn enqueue(job: impl FnOnce() + Send + 'static) -> Result<(), String> {
+    let tx = QUEUE.lock().map_err(|e| format!("Lock error: {}", e))?;
+    match tx.try_send(Box::new(job)) {
+        Ok(()) => Ok(()),
+        Err(TrySendError::Full(_)) => {
+            Err(format!("Task queue is full ({} jobs pending); try again shortly", QUEUE_CAPACITY))
+        }
+        Err(TrySendError::Disconnected(_)) => Err("Task queue worker is not running".to_string()),
+    }
+}
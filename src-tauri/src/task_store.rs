@@ -0,0 +1,181 @@
+// Durable task store for long-running commands (currently `generate_embeddings`
+// and `enqueue_scan`'s directory scans). Tasks are persisted to
+// `tasks.json` under the index directory so a job's history and final status
+// survive a restart; cancellation is additionally tracked in-memory, since a
+// running task can only be interrupted while this process is still alive.
+
+use chrono::Local;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Enqueued,
+    Processing,
+    Succeeded,
+    Failed,
+    Canceled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Task {
+    pub id: String,
+    pub kind: String,
+    pub status: TaskStatus,
+    pub enqueued_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    // Typically a serialized `BatchProgress`, but left generic so other task
+    // kinds (clustering, indexing) can reuse this store with their own shape.
+    pub details: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct TaskLog {
+    tasks: Vec<Task>,
+}
+
+// In-memory cancellation flags for tasks running in this process, keyed by
+// task id. `cancel_task` flips the flag a running loop polls via
+// `is_canceled`, and also updates the on-disk record so the status survives
+// even if the process (and the flag with it) is gone by the time anyone
+// looks. A task with no flag here is simply not running in this process.
+static CANCEL_FLAGS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn tasks_file(index_dir: &str) -> PathBuf {
+    Path::new(index_dir).join("tasks.json")
+}
+
+fn load_log(index_dir: &str) -> TaskLog {
+    fs::read_to_string(tasks_file(index_dir))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_log(index_dir: &str, log: &TaskLog) -> Result<(), String> {
+    fs::create_dir_all(index_dir).map_err(|e| format!("Failed to create index directory: {}", e))?;
+    let json = serde_json::to_string_pretty(log).map_err(|e| format!("Failed to serialize tasks: {}", e))?;
+    fs::write(tasks_file(index_dir), json).map_err(|e| format!("Failed to write tasks file: {}", e))
+}
+
+fn update_task(index_dir: &str, id: &str, f: impl FnOnce(&mut Task)) -> Result<Task, String> {
+    let mut log = load_log(index_dir);
+    let task = log
+        .tasks
+        .iter_mut()
+        .find(|t| t.id == id)
+        .ok_or_else(|| format!("Task not found: {}", id))?;
+    f(task);
+    let updated = task.clone();
+    save_log(index_dir, &log)?;
+    Ok(updated)
+}
+
+/// Enqueue a new task in `Enqueued` status and register a fresh
+/// cancellation flag for it.
+pub fn enqueue_task(index_dir: &str, kind: &str, details: serde_json::Value) -> Result<Task, String> {
+    let mut log = load_log(index_dir);
+    let id = format!("task_{}_{}", kind, Local::now().timestamp_millis());
+
+    let task = Task {
+        id: id.clone(),
+        kind: kind.to_string(),
+        status: TaskStatus::Enqueued,
+        enqueued_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        started_at: None,
+        finished_at: None,
+        details,
+    };
+
+    log.tasks.push(task.clone());
+    save_log(index_dir, &log)?;
+
+    CANCEL_FLAGS
+        .lock()
+        .map_err(|e| format!("Lock error: {}", e))?
+        .insert(id, Arc::new(AtomicBool::new(false)));
+
+    Ok(task)
+}
+
+pub fn list_tasks(index_dir: &str) -> Vec<Task> {
+    load_log(index_dir).tasks
+}
+
+pub fn get_task(index_dir: &str, id: &str) -> Option<Task> {
+    load_log(index_dir).tasks.into_iter().find(|t| t.id == id)
+}
+
+/// Mark a task `Processing` and record its start time. Called just before
+/// the task's body starts running.
+pub fn start_task(index_dir: &str, id: &str) -> Result<Task, String> {
+    update_task(index_dir, id, |task| {
+        // A task canceled while still enqueued stays `Canceled`.
+        if task.status != TaskStatus::Canceled {
+            task.status = TaskStatus::Processing;
+        }
+        task.started_at = Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+    })
+}
+
+/// Update a task's `details` (typically a serialized `BatchProgress`)
+/// without changing its status. Called periodically while a task runs.
+pub fn update_task_details(index_dir: &str, id: &str, details: serde_json::Value) -> Result<Task, String> {
+    update_task(index_dir, id, |task| {
+        task.details = details;
+    })
+}
+
+/// Mark a task finished: `Succeeded` or `Failed` depending on `success`,
+/// unless it was already `Canceled` mid-run, which takes precedence.
+pub fn finish_task(index_dir: &str, id: &str, success: bool, details: serde_json::Value) -> Result<Task, String> {
+    let finished = update_task(index_dir, id, |task| {
+        if task.status != TaskStatus::Canceled {
+            task.status = if success { TaskStatus::Succeeded } else { TaskStatus::Failed };
+        }
+        task.finished_at = Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        task.details = details;
+    })?;
+
+    if let Ok(mut flags) = CANCEL_FLAGS.lock() {
+        flags.remove(id);
+    }
+
+    Ok(finished)
+}
+
+/// Flip a task to `Canceled` on disk and signal its in-memory flag (a no-op
+/// on the flag if the task isn't running in this process, e.g. after a
+/// restart — the on-disk status change still takes effect).
+pub fn cancel_task(index_dir: &str, id: &str) -> Result<Task, String> {
+    if let Ok(flags) = CANCEL_FLAGS.lock() {
+        if let Some(flag) = flags.get(id) {
+            flag.store(true, Ordering::SeqCst);
+        }
+    }
+
+    update_task(index_dir, id, |task| {
+        if matches!(task.status, TaskStatus::Enqueued | TaskStatus::Processing) {
+            task.status = TaskStatus::Canceled;
+            task.finished_at = Some(Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+    })
+}
+
+/// Whether `cancel_task` has been called for this task in this process. A
+/// task with no in-memory flag (not running here, or already finished) is
+/// reported as not canceled — its on-disk status is authoritative instead.
+pub fn is_canceled(id: &str) -> bool {
+    CANCEL_FLAGS
+        .lock()
+        .ok()
+        .and_then(|flags| flags.get(id).map(|f| f.load(Ordering::SeqCst)))
+        .unwrap_or(false)
+}
@@ -0,0 +1,415 @@
+// An approximate-nearest-neighbor index over embedding vectors, so
+// semantic search and clustering don't have to brute-force every row in
+// `embeddings.json` (O(N) per query, which stops scaling past a few
+// thousand files). Implements HNSW (Hierarchical Navigable Small World),
+// persisted next to the embeddings it was built from as `vector_index.bin`.
+//
+// The brute-force cosine-distance scan this replaces is kept around
+// (`brute_force_search`) as the fallback for small indexes, for when no
+// index has been built yet, and as the ground truth correctness tests
+// below compare recall against.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn cosine_distance(a: &[f32], b: &[f32]) -> f32 {
+    let mut dot = 0.0f32;
+    let mut norm_a = 0.0f32;
+    let mut norm_b = 0.0f32;
+
+    for i in 0..a.len().min(b.len()) {
+        dot += a[i] * b[i];
+        norm_a += a[i] * a[i];
+        norm_b += b[i] * b[i];
+    }
+
+    let similarity = dot / (norm_a.sqrt() * norm_b.sqrt() + 1e-10);
+    1.0 - similarity
+}
+
+/// A vector under management by the index, identified by its position in
+/// whatever `Vec<FileEmbedding>` it was built from (callers map `id` back
+/// to a path/chunk themselves; the index only deals in ids and vectors).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct HnswNode {
+    vector: Vec<f32>,
+    /// `layers[l]` holds this node's neighbor ids at graph layer `l`.
+    layers: Vec<Vec<usize>>,
+}
+
+/// Construction/search tuning. Defaults follow the values the original
+/// HNSW paper found to work well in practice.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HnswParams {
+    /// Neighbors kept per node per layer above layer 0 (layer 0 keeps `2*m`).
+    pub m: usize,
+    /// Candidate list size used while inserting.
+    pub ef_construction: usize,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self { m: 16, ef_construction: 200 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HnswIndex {
+    nodes: Vec<HnswNode>,
+    entry_point: Option<usize>,
+    params: HnswParams,
+}
+
+impl HnswIndex {
+    pub fn new(params: HnswParams) -> Self {
+        Self { nodes: Vec::new(), entry_point: None, params }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// `mL` from the paper: `1 / ln(M)`, the scale of the exponential
+    /// distribution new nodes draw their top layer from.
+    fn level_scale(&self) -> f32 {
+        1.0 / (self.params.m as f32).ln()
+    }
+
+    /// Build an index from scratch over every vector in `vectors`, inserting
+    /// one at a time in the given order. `seed` drives the per-node random
+    /// layer assignment so a build is reproducible given the same input.
+    pub fn build(vectors: Vec<Vec<f32>>, params: HnswParams, seed: u64) -> Self {
+        let mut index = Self::new(params);
+        let mut rng = SplitMix64::new(seed);
+        for vector in vectors {
+            index.insert(vector, &mut rng);
+        }
+        index
+    }
+
+    /// Insert one vector, assigning it a random top layer via
+    /// `L = floor(-ln(U) * mL)`, greedily descending from the current entry
+    /// point to find the closest node on each layer above `L`, then at each
+    /// layer from `L` down to 0 running a best-first search with
+    /// `ef_construction` candidates and connecting to its `M` (or `2*M` at
+    /// layer 0) closest, diversity-pruned neighbors.
+    fn insert(&mut self, vector: Vec<f32>, rng: &mut SplitMix64) {
+        let id = self.nodes.len();
+        let u = rng.next_f32().max(f32::MIN_POSITIVE);
+        let top_layer = (-u.ln() * self.level_scale()).floor() as usize;
+
+        let node = HnswNode { vector, layers: vec![Vec::new(); top_layer + 1] };
+        let query = node.vector.clone();
+        self.nodes.push(node);
+
+        let entry_point = match self.entry_point {
+            None => {
+                self.entry_point = Some(id);
+                return;
+            }
+            Some(ep) => ep,
+        };
+
+        let entry_layer = self.nodes[entry_point].layers.len() - 1;
+        let mut current = entry_point;
+
+        // Greedily descend through layers above this node's top layer,
+        // always stepping to whichever neighbor is closest to `query`.
+        for layer in (top_layer + 1..=entry_layer).rev() {
+            current = self.greedy_closest(current, &query, layer);
+        }
+
+        // From `top_layer` down to 0, run a best-first search and connect.
+        for layer in (0..=top_layer.min(entry_layer)).rev() {
+            let candidates = self.search_layer(&query, current, self.params.ef_construction, layer);
+            let max_neighbors = if layer == 0 { self.params.m * 2 } else { self.params.m };
+            let selected = self.select_neighbors(&query, candidates, max_neighbors);
+
+            for &neighbor_id in &selected {
+                self.nodes[id].layers[layer].push(neighbor_id);
+                self.nodes[neighbor_id].layers[layer].push(id);
+                // Prune the neighbor's list back down if this new mutual
+                // link pushed it over budget.
+                if self.nodes[neighbor_id].layers[layer].len() > max_neighbors {
+                    let nb_vector = self.nodes[neighbor_id].vector.clone();
+                    let nb_candidates = self.nodes[neighbor_id].layers[layer].clone();
+                    let pruned = self.select_neighbors(&nb_vector, nb_candidates, max_neighbors);
+                    self.nodes[neighbor_id].layers[layer] = pruned;
+                }
+            }
+            if let Some(&closest) = selected.first() {
+                current = closest;
+            }
+        }
+
+        if top_layer > entry_layer {
+            self.entry_point = Some(id);
+        }
+    }
+
+    fn greedy_closest(&self, mut current: usize, query: &[f32], layer: usize) -> usize {
+        loop {
+            let mut best = current;
+            let mut best_dist = cosine_distance(query, &self.nodes[current].vector);
+            for &neighbor in &self.nodes[current].layers[layer] {
+                let dist = cosine_distance(query, &self.nodes[neighbor].vector);
+                if dist < best_dist {
+                    best = neighbor;
+                    best_dist = dist;
+                }
+            }
+            if best == current {
+                return current;
+            }
+            current = best;
+        }
+    }
+
+    /// Best-first search of `layer` starting from `entry`, keeping up to
+    /// `ef` candidates by distance to `query`. Returns ids sorted closest
+    /// first.
+    fn search_layer(&self, query: &[f32], entry: usize, ef: usize, layer: usize) -> Vec<usize> {
+        use std::collections::HashSet;
+
+        let mut visited: HashSet<usize> = HashSet::new();
+        visited.insert(entry);
+
+        let entry_dist = cosine_distance(query, &self.nodes[entry].vector);
+        let mut candidates: Vec<(f32, usize)> = vec![(entry_dist, entry)];
+        let mut found: Vec<(f32, usize)> = vec![(entry_dist, entry)];
+
+        while let Some(&(dist, node)) = candidates.iter().min_by(|a, b| a.0.partial_cmp(&b.0).unwrap()) {
+            candidates.retain(|c| c.1 != node);
+
+            let worst_found = found
+                .iter()
+                .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+                .map(|&(d, _)| d)
+                .unwrap_or(f32::INFINITY);
+            if dist > worst_found && found.len() >= ef {
+                break;
+            }
+
+            for &neighbor in &self.nodes[node].layers[layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let neighbor_dist = cosine_distance(query, &self.nodes[neighbor].vector);
+                let worst_found = found
+                    .iter()
+                    .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+                    .map(|&(d, _)| d)
+                    .unwrap_or(f32::INFINITY);
+                if found.len() < ef || neighbor_dist < worst_found {
+                    candidates.push((neighbor_dist, neighbor));
+                    found.push((neighbor_dist, neighbor));
+                    if found.len() > ef {
+                        let worst_idx = found
+                            .iter()
+                            .enumerate()
+                            .max_by(|a, b| a.1 .0.partial_cmp(&b.1 .0).unwrap())
+                            .map(|(i, _)| i)
+                            .unwrap();
+                        found.swap_remove(worst_idx);
+                    }
+                }
+            }
+        }
+
+        found.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        found.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Prune `candidates` down to `max_neighbors`, preferring ones that are
+    /// both close to `query` and diverse from each other (a candidate is
+    /// dropped if it's closer to an already-selected neighbor than it is to
+    /// `query`), so a node doesn't end up with a cluster of near-duplicate
+    /// links pointing the same direction.
+    fn select_neighbors(&self, query: &[f32], mut candidates: Vec<usize>, max_neighbors: usize) -> Vec<usize> {
+        candidates.sort_by(|&a, &b| {
+            cosine_distance(query, &self.nodes[a].vector)
+                .partial_cmp(&cosine_distance(query, &self.nodes[b].vector))
+                .unwrap()
+        });
+
+        let mut selected: Vec<usize> = Vec::new();
+        for candidate in candidates {
+            if selected.len() >= max_neighbors {
+                break;
+            }
+            let dist_to_query = cosine_distance(query, &self.nodes[candidate].vector);
+            let dominated = selected.iter().any(|&s| {
+                cosine_distance(&self.nodes[candidate].vector, &self.nodes[s].vector) < dist_to_query
+            });
+            if !dominated {
+                selected.push(candidate);
+            }
+        }
+        selected
+    }
+
+    /// Approximate top-`top_k` nearest neighbors to `query` by cosine
+    /// distance: greedy-descend to layer 0 then run a best-first search
+    /// with an `ef_search` beam. Returns `(id, distance)` closest first.
+    pub fn search(&self, query: &[f32], top_k: usize, ef_search: usize) -> Vec<(usize, f32)> {
+        let Some(entry_point) = self.entry_point else { return Vec::new() };
+
+        let entry_layer = self.nodes[entry_point].layers.len() - 1;
+        let mut current = entry_point;
+        for layer in (1..=entry_layer).rev() {
+            current = self.greedy_closest(current, query, layer);
+        }
+
+        let ef = ef_search.max(top_k);
+        let mut found = self.search_layer(query, current, ef, 0);
+        found.truncate(top_k);
+        found
+            .into_iter()
+            .map(|id| (id, cosine_distance(query, &self.nodes[id].vector)))
+            .collect()
+    }
+}
+
+/// Exhaustive, always-correct nearest-neighbor scan. Used directly for
+/// small indexes (where building an HNSW graph isn't worth it) and as the
+/// recall baseline in this module's tests.
+pub fn brute_force_search(vectors: &[Vec<f32>], query: &[f32], top_k: usize) -> Vec<(usize, f32)> {
+    let mut scored: Vec<(usize, f32)> = vectors
+        .iter()
+        .enumerate()
+        .map(|(id, v)| (id, cosine_distance(query, v)))
+        .collect();
+    scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+    scored.truncate(top_k);
+    scored
+}
+
+/// Below this many vectors, brute force is both fast enough and simpler
+/// than standing up a graph, so `build_vector_index` skips the HNSW build
+/// entirely and callers should prefer `brute_force_search`.
+pub const MIN_VECTORS_FOR_INDEX: usize = 500;
+
+fn vector_index_path(index_dir: &str) -> PathBuf {
+    Path::new(index_dir).join("vector_index.bin")
+}
+
+pub fn vector_index_exists(index_dir: &str) -> bool {
+    vector_index_path(index_dir).exists()
+}
+
+/// Persist `index` next to `embeddings.json`. Stored as a JSON document
+/// like every other file this codebase persists, just under a `.bin`
+/// extension to signal it's an opaque, internal artifact rather than
+/// something a user is meant to open.
+pub fn save_index(index_dir: &str, index: &HnswIndex) -> Result<(), String> {
+    let json = serde_json::to_string(index).map_err(|e| format!("Failed to serialize vector index: {}", e))?;
+    fs::write(vector_index_path(index_dir), json).map_err(|e| format!("Failed to write vector index: {}", e))
+}
+
+pub fn load_index(index_dir: &str) -> Result<HnswIndex, String> {
+    let content = fs::read_to_string(vector_index_path(index_dir))
+        .map_err(|e| format!("Failed to read vector index: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse vector index: {}", e))
+}
+
+/// Minimal splitmix64 PRNG so index construction is reproducible without
+/// pulling in a general-purpose RNG crate just for layer assignment.
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in `(0, 1]`, never `0.0` so `-ln(u)` stays finite.
+    fn next_f32(&mut self) -> f32 {
+        let bits = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        1.0 - bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn random_vectors(count: usize, dims: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut rng = SplitMix64::new(seed);
+        (0..count)
+            .map(|_| (0..dims).map(|_| rng.next_f32() * 2.0 - 1.0).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_build_and_search_matches_brute_force_top1() {
+        let vectors = random_vectors(300, 16, 42);
+        let index = HnswIndex::build(vectors.clone(), HnswParams::default(), 7);
+
+        let query = random_vectors(1, 16, 99).remove(0);
+        let approx = index.search(&query, 5, 100);
+        let exact = brute_force_search(&vectors, &query, 5);
+
+        assert_eq!(approx.len(), 5);
+        // HNSW is approximate, but with this few vectors and a generous
+        // `ef_search` it should recover the true nearest neighbor.
+        assert_eq!(approx[0].0, exact[0].0);
+    }
+
+    #[test]
+    fn test_search_recall_is_reasonably_high() {
+        let vectors = random_vectors(500, 32, 123);
+        let index = HnswIndex::build(vectors.clone(), HnswParams::default(), 5);
+
+        let mut hits = 0;
+        let trials = 20;
+        for i in 0..trials {
+            let query = vectors[i * 7 % vectors.len()].clone();
+            let approx: std::collections::HashSet<usize> =
+                index.search(&query, 10, 100).into_iter().map(|(id, _)| id).collect();
+            let exact: std::collections::HashSet<usize> =
+                brute_force_search(&vectors, &query, 10).into_iter().map(|(id, _)| id).collect();
+            hits += approx.intersection(&exact).count();
+        }
+        // Recall@10 averaged over the trials; HNSW trades a bit of recall
+        // for speed, so this only checks it isn't badly broken.
+        let recall = hits as f32 / (trials * 10) as f32;
+        assert!(recall > 0.7, "recall too low: {}", recall);
+    }
+
+    #[test]
+    fn test_empty_index_search_returns_nothing() {
+        let index = HnswIndex::new(HnswParams::default());
+        assert!(index.search(&[1.0, 0.0], 5, 50).is_empty());
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("wayfinder_vi_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir_str = dir.to_string_lossy().to_string();
+
+        let vectors = random_vectors(50, 8, 1);
+        let index = HnswIndex::build(vectors, HnswParams::default(), 2);
+        save_index(&dir_str, &index).unwrap();
+
+        assert!(vector_index_exists(&dir_str));
+        let loaded = load_index(&dir_str).unwrap();
+        assert_eq!(loaded.len(), index.len());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
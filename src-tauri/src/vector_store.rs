@@ -0,0 +1,146 @@
+// Optional Postgres/pgvector-backed embedding index, for deployments where
+// `generate_embeddings` should write into a shared store that survives
+// past a single `index_dir` and serves concurrent searches — the on-disk
+// `embeddings.json`/`vector_index.bin` pair `index_store.rs` and
+// `vector_index.rs` manage is per-index and single-process by design.
+//
+// Gated behind the `pgvector` Cargo feature, so a build that doesn't need
+// it doesn't pull in `bb8`/`tokio-postgres`:
+//   [features]
+//   pgvector = ["dep:bb8", "dep:bb8-postgres", "dep:tokio-postgres"]
+
+#![cfg(feature = "pgvector")]
+
+use bb8::Pool;
+use bb8_postgres::PostgresConnectionManager;
+use serde::Serialize;
+use tokio_postgres::NoTls;
+
+/// One ranked hit out of [`PgVectorStore::search`].
+#[derive(Serialize, Debug, Clone)]
+pub struct VectorHit {
+    pub file_path: String,
+    pub chunk_id: i32,
+    /// Cosine distance (`<=>`); lower is more similar, 0.0 is identical.
+    pub distance: f64,
+    pub metadata: serde_json::Value,
+}
+
+/// A pooled connection to a Postgres database with the `pgvector` extension
+/// enabled, storing one row per embedded chunk in an `embeddings` table.
+/// Calls on one store share the pool's connections, rather than the single
+/// connection `PostgresStorageBackend` opens per command. A store is not
+/// kept between commands: `generate_embeddings` and `search_embeddings`
+/// each connect a new one, so every `search_embeddings` call pays pool and
+/// connection setup.
+pub struct PgVectorStore {
+    pool: Pool<PostgresConnectionManager<NoTls>>,
+    dimensions: usize,
+}
+
+impl PgVectorStore {
+    /// Connect to `database_url`, enable the `vector` extension, and
+    /// create the `embeddings` table if it doesn't exist yet. `dimensions`
+    /// must match the configured embedding provider's output size, since
+    /// `vector(N)` is a fixed-width Postgres column type.
+    pub async fn connect(database_url: &str, dimensions: usize) -> Result<Self, String> {
+        let manager = PostgresConnectionManager::new_from_stringlike(database_url, NoTls)
+            .map_err(|e| format!("Invalid Postgres connection string: {}", e))?;
+        let pool = Pool::builder()
+            .build(manager)
+            .await
+            .map_err(|e| format!("Failed to build Postgres connection pool: {}", e))?;
+
+        let conn = pool.get().await.map_err(|e| format!("Failed to get a pooled connection: {}", e))?;
+        conn.batch_execute("CREATE EXTENSION IF NOT EXISTS vector")
+            .await
+            .map_err(|e| format!("Failed to enable the pgvector extension: {}", e))?;
+        conn.batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS embeddings (
+                file_path TEXT NOT NULL,
+                chunk_id INT NOT NULL,
+                embedding vector({dimensions}) NOT NULL,
+                metadata JSONB NOT NULL DEFAULT '{{}}'::jsonb,
+                PRIMARY KEY (file_path, chunk_id)
+            )"
+        ))
+        .await
+        .map_err(|e| format!("Failed to create embeddings table: {}", e))?;
+
+        Ok(Self { pool, dimensions })
+    }
+
+    /// Upsert one batch of `(file_path, chunk_id, embedding, metadata)`
+    /// rows, replacing any existing row for the same `(file_path, chunk_id)`
+    /// so a re-run of `generate_embeddings` overwrites stale vectors rather
+    /// than accumulating duplicates.
+    pub async fn upsert_batch(
+        &self,
+        rows: &[(String, i32, Vec<f32>, serde_json::Value)],
+    ) -> Result<(), String> {
+        let conn = self.pool.get().await.map_err(|e| format!("Failed to get a pooled connection: {}", e))?;
+
+        for (file_path, chunk_id, embedding, metadata) in rows {
+            if embedding.len() != self.dimensions {
+                return Err(format!(
+                    "Embedding for {}#{} has {} dimensions, expected {}",
+                    file_path, chunk_id, embedding.len(), self.dimensions
+                ));
+            }
+
+            conn.execute(
+                "INSERT INTO embeddings (file_path, chunk_id, embedding, metadata)
+                 VALUES ($1, $2, $3::vector, $4)
+                 ON CONFLICT (file_path, chunk_id)
+                 DO UPDATE SET embedding = excluded.embedding, metadata = excluded.metadata",
+                &[file_path, chunk_id, &to_pgvector_literal(embedding), metadata],
+            )
+            .await
+            .map_err(|e| format!("Failed to upsert embedding for {}#{}: {}", file_path, chunk_id, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Rank stored chunks against `query_vec` by cosine distance (pgvector's
+    /// `<=>` operator) and return the `k` closest.
+    pub async fn search(&self, query_vec: &[f32], k: usize) -> Result<Vec<VectorHit>, String> {
+        let conn = self.pool.get().await.map_err(|e| format!("Failed to get a pooled connection: {}", e))?;
+
+        let rows = conn
+            .query(
+                "SELECT file_path, chunk_id, metadata, embedding <=> $1::vector AS distance
+                 FROM embeddings
+                 ORDER BY embedding <=> $1::vector
+                 LIMIT $2",
+                &[&to_pgvector_literal(query_vec), &(k as i64)],
+            )
+            .await
+            .map_err(|e| format!("Failed to search embeddings: {}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| VectorHit {
+                file_path: row.get(0),
+                chunk_id: row.get(1),
+                metadata: row.get(2),
+                distance: row.get(3),
+            })
+            .collect())
+    }
+}
+
+/// pgvector accepts its `vector` type as a literal of the form `[1,2,3]`
+/// over the wire; there's no native `tokio-postgres` type for it.
+fn to_pgvector_literal(v: &[f32]) -> String {
+    let mut s = String::with_capacity(v.len() * 8 + 2);
+    s.push('[');
+    for (i, x) in v.iter().enumerate() {
+        if i > 0 {
+            s.push(',');
+        }
+        s.push_str(&x.to_string());
+    }
+    s.push(']');
+    s
+}